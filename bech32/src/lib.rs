@@ -24,6 +24,13 @@
 //!
 //! Bech32 `id1...` representation is provided automatically only for hash types
 //! implementing [`bitcoin_hashes::Hash`] trait
+//!
+//! With `elgamal` feature, any strict-encoded type can also be represented as
+//! an encrypted `enc1...` Bech32 string (see [`encrypted`] module).
+//!
+//! With `test-helpers` feature, the crate provides conformance kit for
+//! checking Bech32 implementations of custom types (see `test_helpers`
+//! module).
 
 #[macro_use]
 extern crate amplify;
@@ -65,6 +72,26 @@ pub const HRP_ZIP: &str = "z";
 #[cfg(feature = "zip")]
 pub const RAW_DATA_ENCODING_DEFLATE: u8 = 1u8;
 
+/// Maximal identifier of a pre-shared compression dictionary. Dictionary
+/// identifier is stored in the upper four bits of the encoding version byte,
+/// with zero meaning that no dictionary is used
+#[cfg(feature = "zip")]
+pub const ZIP_DICTIONARY_ID_MAX: u8 = 0x0F;
+
+/// Maximal length of a pre-shared compression dictionary, matching DEFLATE
+/// window size
+#[cfg(feature = "zip")]
+pub const ZIP_DICTIONARY_MAX_LEN: usize = 32 * 1024;
+
+/// Bech32 HRP used for representation of encrypted data
+#[cfg(feature = "elgamal")]
+pub const HRP_ENCRYPTED: &str = "enc";
+
+/// Constant specifying default encryption algorithm (LNPBP-31 ElGamal
+/// encryption with Secp256k1 curve)
+#[cfg(feature = "elgamal")]
+pub const ENCRYPTION_ELGAMAL_SECP256K1: u8 = 1u8;
+
 /// Errors generated by Bech32 conversion functions (both parsing and
 /// type-specific conversion errors)
 #[derive(Clone, PartialEq, Eq, Display, Debug, From, Error)]
@@ -99,6 +126,32 @@ pub enum Error {
 
     /// error inflating compressed data from payload: {0}
     InflateError(String),
+
+    /// compressed data use unknown pre-shared dictionary {0}
+    UnknownZipDictionary(u8),
+
+    /// provided encrypted data use unknown encryption algorithm {0}
+    #[cfg(feature = "elgamal")]
+    UnknownEncryption(u8),
+
+    /// encrypted data contain invalid unblinding key
+    #[cfg(feature = "elgamal")]
+    InvalidUnblindingKey,
+
+    /// data of {0} bytes are too large to be encrypted; at most 2^32-1 bytes
+    /// can be encrypted
+    #[cfg(feature = "elgamal")]
+    PayloadTooLarge(usize),
+
+    /// decrypted data have invalid length prefix, which usually means that a
+    /// wrong decryption key was used
+    #[cfg(feature = "elgamal")]
+    InvalidDecryptedLength,
+
+    /// error encrypting or decrypting data: {0}
+    #[cfg(feature = "elgamal")]
+    #[from]
+    Encryption(lnpbp_elgamal::Error),
 }
 
 impl From<Infallible> for Error {
@@ -247,6 +300,12 @@ pub mod strategies {
         /// Specific strategy used for automatic implementation of all
         /// Bech32-related traits.
         type Strategy;
+        /// Domain-specific pre-shared dictionary improving compression of
+        /// small payloads. Used only by types with
+        /// [`CompressedStrictEncoding`] strategy; all parties exchanging
+        /// such data must use the same dictionary for the type.
+        #[cfg(feature = "zip")]
+        const ZIP_DICTIONARY: Option<ZipDictionary> = None;
     }
 
     impl<T> ToBech32String for T
@@ -418,28 +477,185 @@ impl<T> FromBech32DataStr for T where T: sealed::FromPayload {}
 #[doc(hidden)]
 #[cfg(feature = "zip")]
 pub mod zip {
+    use std::cell::Cell;
+    use std::io::{self, Write};
+
     use amplify::Holder;
     use strict_encoding::{StrictDecode, StrictEncode};
 
     use super::*;
 
-    fn payload_to_bech32_zip_string(hrp: &str, payload: &[u8]) -> String {
-        use std::io::Write;
+    /// Pre-shared dictionary for DEFLATE compression, allowing efficient
+    /// compression of small payloads sharing common byte sequences with the
+    /// dictionary.
+    ///
+    /// Dictionary is identified by a number from 1 to
+    /// [`ZIP_DICTIONARY_ID_MAX`], which is stored in the encoding version
+    /// byte. Types with [`strategies::CompressedStrictEncoding`] strategy
+    /// register dictionaries via [`Strategy::ZIP_DICTIONARY`] constant.
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    pub struct ZipDictionary {
+        id: u8,
+        data: &'static [u8],
+    }
+
+    impl ZipDictionary {
+        /// Constructs dictionary with a given identifier from the data.
+        ///
+        /// # Panics
+        ///
+        /// If `id` is zero or exceeds [`ZIP_DICTIONARY_ID_MAX`], or if the
+        /// data are longer than [`ZIP_DICTIONARY_MAX_LEN`]. When used in
+        /// const context, this results in a compile-time error.
+        pub const fn with(id: u8, data: &'static [u8]) -> ZipDictionary {
+            assert!(
+                id > 0 && id <= ZIP_DICTIONARY_ID_MAX,
+                "zip dictionary id must be in range 1..=15"
+            );
+            assert!(
+                data.len() <= ZIP_DICTIONARY_MAX_LEN,
+                "zip dictionary can't exceed DEFLATE window size"
+            );
+            ZipDictionary { id, data }
+        }
+
+        /// Returns dictionary identifier
+        #[inline]
+        pub fn id(&self) -> u8 { self.id }
+
+        /// Returns dictionary data
+        #[inline]
+        pub fn data(&self) -> &'static [u8] { self.data }
+
+        /// Returns encoding version byte used by the data compressed with the
+        /// dictionary
+        #[inline]
+        pub fn version_byte(&self) -> u8 {
+            self.id << 4 | RAW_DATA_ENCODING_DEFLATE
+        }
+    }
+
+    /// Writer tracking the amount of data written, which allows to locate
+    /// compressed data following the dictionary
+    struct TrackingWriter<'len> {
+        data: Vec<u8>,
+        len: &'len Cell<usize>,
+    }
+
+    impl Write for TrackingWriter<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.data.extend_from_slice(buf);
+            self.len.set(self.data.len());
+            Ok(buf.len())
+        }
 
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    /// Compresses the data, prefixing them with the encoding version byte.
+    ///
+    /// With a dictionary, the compressor is first fed with the dictionary
+    /// data and flushed, such that the following data may reference the
+    /// dictionary; the part of the output produced by the dictionary is
+    /// dropped. The result is used only if it is shorter than the data
+    /// compressed without the dictionary.
+    pub(crate) fn deflate_payload(
+        payload: &[u8],
+        dictionary: Option<&ZipDictionary>,
+    ) -> Vec<u8> {
         // We initialize writer with a version byte, indicating deflation
         // algorithm used
-        let writer = vec![RAW_DATA_ENCODING_DEFLATE];
+        let mut encoder = DeflateEncoder::new(
+            vec![RAW_DATA_ENCODING_DEFLATE],
+            Compression::Best,
+        );
+        encoder
+            .write_all(payload)
+            .expect("in-memory strict encoder failure");
+        let plain = encoder.finish().expect("zip algorithm failure");
+
+        let dictionary = match dictionary {
+            Some(dictionary) => dictionary,
+            None => return plain,
+        };
+
+        let len = Cell::new(0usize);
+        let writer = TrackingWriter {
+            data: vec![],
+            len: &len,
+        };
         let mut encoder = DeflateEncoder::new(writer, Compression::Best);
+        encoder
+            .write_all(dictionary.data)
+            .and_then(|_| encoder.flush())
+            .expect("in-memory strict encoder failure");
+        let skip = len.get();
         encoder
             .write_all(payload)
             .expect("in-memory strict encoder failure");
-        let data = encoder.finish().expect("zip algorithm failure");
+        let compressed = encoder.finish().expect("zip algorithm failure").data;
+
+        if compressed.len() - skip + 1 >= plain.len() {
+            return plain;
+        }
+        let mut data = Vec::with_capacity(compressed.len() - skip + 1);
+        data.push(dictionary.version_byte());
+        data.extend_from_slice(&compressed[skip..]);
+        data
+    }
+
+    /// Decompresses the data prefixed with the encoding version byte.
+    ///
+    /// Data compressed with a dictionary are prefixed with a non-final
+    /// DEFLATE stored block containing the dictionary, such that the
+    /// decompressor window contains the dictionary data.
+    pub(crate) fn inflate_payload(
+        data: &[u8],
+        dictionary: Option<&ZipDictionary>,
+    ) -> Result<Vec<u8>, Error> {
+        let version = *data.first().ok_or(Error::NoEncodingPrefix)?;
+        if version & ZIP_DICTIONARY_ID_MAX != RAW_DATA_ENCODING_DEFLATE {
+            return Err(Error::UnknownRawDataEncoding(version));
+        }
+        let dictionary = match (version >> 4, dictionary) {
+            (0, _) => {
+                return inflate::inflate_bytes(&data[1..])
+                    .map_err(Error::InflateError)
+            }
+            (id, Some(dictionary)) if id == dictionary.id => dictionary,
+            (id, _) => return Err(Error::UnknownZipDictionary(id)),
+        };
+
+        let dict_len = dictionary.data.len();
+        let mut stream = Vec::with_capacity(5 + dict_len + data.len());
+        // Non-final stored block header: BFINAL = 0, BTYPE = 00, followed by
+        // the block length and its one's complement
+        stream.push(0);
+        stream.extend_from_slice(&(dict_len as u16).to_le_bytes());
+        stream.extend_from_slice(&(!(dict_len as u16)).to_le_bytes());
+        stream.extend_from_slice(dictionary.data);
+        stream.extend_from_slice(&data[1..]);
+        let mut decoded =
+            inflate::inflate_bytes(&stream).map_err(Error::InflateError)?;
+        decoded.drain(..dict_len);
+        Ok(decoded)
+    }
 
+    fn payload_to_bech32_zip_string(
+        hrp: &str,
+        payload: &[u8],
+        dictionary: Option<&ZipDictionary>,
+    ) -> String {
+        let data = deflate_payload(payload, dictionary);
         ::bech32::encode(hrp, data.to_base32(), Variant::Bech32m)
             .expect("HRP is hardcoded and can't fail")
     }
 
-    fn bech32_zip_str_to_payload(hrp: &str, s: &str) -> Result<Vec<u8>, Error> {
+    fn bech32_zip_str_to_payload(
+        hrp: &str,
+        s: &str,
+        dictionary: Option<&ZipDictionary>,
+    ) -> Result<Vec<u8>, Error> {
         let (prefix, data, version) = bech32::decode(s)?;
         if prefix != hrp {
             return Err(Error::WrongPrefix);
@@ -448,14 +664,7 @@ pub mod zip {
             return Err(Error::WrongVariant);
         }
         let data = Vec::<u8>::from_base32(&data)?;
-        match *data[..].first().ok_or(Error::NoEncodingPrefix)? {
-            RAW_DATA_ENCODING_DEFLATE => {
-                let decoded = inflate::inflate_bytes(&data[1..])
-                    .map_err(Error::InflateError)?;
-                Ok(decoded)
-            }
-            unknown_ver => Err(Error::UnknownRawDataEncoding(unknown_ver)),
-        }
+        inflate_payload(&data, dictionary)
     }
 
     /// Trait for creating `z1...` (compressed binary data blob) Bech32
@@ -464,7 +673,11 @@ pub mod zip {
         /// Returns `z1...` (compressed binary data blob) Bech32 representation
         /// of a given type
         fn to_bech32_zip_string(&self) -> String {
-            payload_to_bech32_zip_string(HRP_ZIP, &self.to_bech32_payload())
+            payload_to_bech32_zip_string(
+                HRP_ZIP,
+                &self.to_bech32_payload(),
+                None,
+            )
         }
     }
 
@@ -476,7 +689,11 @@ pub mod zip {
         /// Returns `z1...` (compressed binary data blob) Bech32 representation
         /// of a given type
         fn bech32_zip_string(&self) -> String {
-            payload_to_bech32_zip_string(HRP_ZIP, self.as_bech32_payload())
+            payload_to_bech32_zip_string(
+                HRP_ZIP,
+                self.as_bech32_payload(),
+                None,
+            )
         }
     }
 
@@ -488,7 +705,9 @@ pub mod zip {
         /// Reconstructs type data from `z1...` (compressed binary data blob)
         /// Bech32 string
         fn from_bech32_zip_str(s: &str) -> Result<Self, Error> {
-            Self::from_bech32_payload(bech32_zip_str_to_payload(HRP_ZIP, s)?)
+            Self::from_bech32_payload(bech32_zip_str_to_payload(
+                HRP_ZIP, s, None,
+            )?)
         }
     }
 
@@ -504,7 +723,11 @@ pub mod zip {
                 .as_inner()
                 .strict_serialize()
                 .expect("in-memory strict encoding failure");
-            payload_to_bech32_zip_string(T::HRP, &data)
+            payload_to_bech32_zip_string(
+                T::HRP,
+                &data,
+                T::ZIP_DICTIONARY.as_ref(),
+            )
         }
     }
 
@@ -517,14 +740,481 @@ pub mod zip {
         #[inline]
         fn from_bech32_str(s: &str) -> Result<Self, Error> {
             Ok(Self::new(T::strict_deserialize(
-                bech32_zip_str_to_payload(Self::HRP, s)?,
+                bech32_zip_str_to_payload(
+                    Self::HRP,
+                    s,
+                    T::ZIP_DICTIONARY.as_ref(),
+                )?,
             )?))
         }
     }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const DICTIONARY: ZipDictionary = ZipDictionary::with(
+            1,
+            b"rgb contract schema genesis transition state owned rights \
+              assignment seal outpoint txid vout amount asset ticker name \
+              precision",
+        );
+
+        #[derive(Clone, PartialEq, Eq, Debug, StrictEncode, StrictDecode)]
+        struct Contract(String);
+
+        impl Strategy for Contract {
+            const HRP: &'static str = "contract";
+            type Strategy = strategies::CompressedStrictEncoding;
+            const ZIP_DICTIONARY: Option<ZipDictionary> = Some(DICTIONARY);
+        }
+
+        fn contract() -> Contract {
+            Contract(s!("rgb contract genesis: asset ticker BTC name bitcoin \
+                         precision 8 amount 100000 seal outpoint txid"))
+        }
+
+        #[test]
+        fn dictionary_roundtrip() {
+            let contract = contract();
+            let data = contract.strict_serialize().unwrap();
+            let plain = deflate_payload(&data, None);
+            let compressed = deflate_payload(&data, Some(&DICTIONARY));
+            assert_eq!(compressed[0], 0x11);
+            assert!(compressed.len() < plain.len());
+            assert_eq!(
+                inflate_payload(&compressed, Some(&DICTIONARY)).unwrap(),
+                data
+            );
+
+            let s = contract.to_bech32_string();
+            assert!(s.starts_with("contract1"));
+            assert_eq!(Contract::from_bech32_str(&s).unwrap(), contract);
+        }
+
+        #[test]
+        fn dictionary_fallback() {
+            // Data not benefiting from the dictionary are compressed without it
+            let data = vec![0u8; 1024];
+            assert_eq!(
+                deflate_payload(&data, Some(&DICTIONARY)),
+                deflate_payload(&data, None)
+            );
+
+            // Data compressed without dictionary are always readable
+            let contract = contract();
+            let plain = payload_to_bech32_zip_string(
+                <Contract as Strategy>::HRP,
+                &contract.strict_serialize().unwrap(),
+                None,
+            );
+            assert_eq!(Contract::from_bech32_str(&plain).unwrap(), contract);
+        }
+
+        #[test]
+        fn dictionary_unknown() {
+            let data = contract().strict_serialize().unwrap();
+            let other = ZipDictionary::with(2, DICTIONARY.data());
+            let compressed = deflate_payload(&data, Some(&other));
+            assert_eq!(
+                inflate_payload(&compressed, Some(&DICTIONARY)).unwrap_err(),
+                Error::UnknownZipDictionary(2)
+            );
+            assert_eq!(
+                inflate_payload(&compressed, None).unwrap_err(),
+                Error::UnknownZipDictionary(2)
+            );
+            assert_eq!(
+                inflate_payload(&[0x12], None).unwrap_err(),
+                Error::UnknownRawDataEncoding(0x12)
+            );
+        }
+    }
 }
 #[cfg(feature = "zip")]
 pub use zip::*;
 
+#[cfg(feature = "elgamal")]
+pub mod encrypted {
+    //! Encrypted `enc1...` Bech32 representation for strict-encoded data.
+    //!
+    //! The data are encrypted according to LNPBP-31 ElGamal scheme for a
+    //! given recipient public key, using a random one-time blinding key. The
+    //! payload of the Bech32 string consists of the encryption algorithm byte,
+    //! serialized unblinding key (public key matching the blinding key) and
+    //! the encrypted data. Since the encryption pads data to a round number of
+    //! chunks, the data are prefixed with their length as a 32-bit
+    //! little-endian integer before the encryption.
+    //!
+    //! Encrypted representation is available for all types implementing
+    //! [`StrictEncode`] and [`StrictDecode`].
+
+    use std::fmt::Display;
+    use std::io::{self, Read};
+
+    use secp256k1::{rand, PublicKey, SecretKey, SECP256K1};
+    use strict_encoding::{StrictDecode, StrictEncode};
+
+    use super::*;
+
+    /// Length of the serialized unblinding key
+    const UNBLINDING_KEY_LEN: usize = 33;
+
+    /// Strict-encoded data encrypted according to LNPBP-31 ElGamal scheme,
+    /// together with the unblinding key required for their decryption.
+    ///
+    /// Displays and parses as `enc1...` Bech32m string.
+    #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+    pub struct EncryptedData {
+        unblinding_key: PublicKey,
+        ciphertext: Vec<u8>,
+    }
+
+    impl EncryptedData {
+        /// Encrypts strict-encoded data, such that they can be decrypted only
+        /// with the private key matching `recipient` public key
+        pub fn encrypt(
+            data: &impl StrictEncode,
+            recipient: PublicKey,
+        ) -> Result<EncryptedData, Error> {
+            EncryptedData::encrypt_bytes(&data.strict_serialize()?, recipient)
+        }
+
+        /// Encrypts raw bytes, such that they can be decrypted only with the
+        /// private key matching `recipient` public key
+        pub fn encrypt_bytes(
+            data: &[u8],
+            recipient: PublicKey,
+        ) -> Result<EncryptedData, Error> {
+            let len = u32::try_from(data.len())
+                .map_err(|_| Error::PayloadTooLarge(data.len()))?;
+            // Length-prefixed data, so we can remove the padding on decryption
+            let mut plaintext = Vec::with_capacity(4 + data.len());
+            plaintext.extend_from_slice(&len.to_le_bytes());
+            plaintext.extend_from_slice(data);
+
+            let mut blinding_key = SecretKey::new(&mut rand::thread_rng());
+            let unblinding_key =
+                PublicKey::from_secret_key(SECP256K1, &blinding_key);
+            let ciphertext = lnpbp_elgamal::encrypt(
+                SECP256K1,
+                &plaintext,
+                recipient,
+                &mut blinding_key,
+            )?;
+            Ok(EncryptedData {
+                unblinding_key,
+                ciphertext,
+            })
+        }
+
+        /// Decrypts the data with the recipient private key and reconstructs
+        /// type data from them
+        pub fn decrypt<T: StrictDecode>(
+            &self,
+            decryption_key: &SecretKey,
+        ) -> Result<T, Error> {
+            Ok(T::strict_deserialize(self.decrypt_bytes(decryption_key)?)?)
+        }
+
+        /// Decrypts raw bytes with the recipient private key
+        pub fn decrypt_bytes(
+            &self,
+            decryption_key: &SecretKey,
+        ) -> Result<Vec<u8>, Error> {
+            let mut decryption_key = *decryption_key;
+            let mut decrypted = lnpbp_elgamal::decrypt(
+                SECP256K1,
+                &self.ciphertext,
+                &mut decryption_key,
+                self.unblinding_key,
+            )?;
+            let len = decrypted
+                .get(..4)
+                .map(|len| {
+                    u32::from_le_bytes([len[0], len[1], len[2], len[3]])
+                        as usize
+                })
+                .filter(|len| *len <= decrypted.len() - 4)
+                .ok_or(Error::InvalidDecryptedLength)?;
+            decrypted.truncate(4 + len);
+            Ok(decrypted.split_off(4))
+        }
+
+        /// Returns unblinding key required for the decryption
+        #[inline]
+        pub fn unblinding_key(&self) -> PublicKey { self.unblinding_key }
+
+        /// Returns encrypted data
+        #[inline]
+        pub fn ciphertext(&self) -> &[u8] { &self.ciphertext }
+
+        /// Returns payload of the `enc1...` Bech32 string: encryption
+        /// algorithm, unblinding key and encrypted data
+        pub fn to_payload(&self) -> Vec<u8> {
+            let mut payload = Vec::with_capacity(
+                1 + UNBLINDING_KEY_LEN + self.ciphertext.len(),
+            );
+            payload.push(ENCRYPTION_ELGAMAL_SECP256K1);
+            payload.extend_from_slice(&self.unblinding_key.serialize());
+            payload.extend_from_slice(&self.ciphertext);
+            payload
+        }
+
+        /// Parses payload of the `enc1...` Bech32 string
+        pub fn from_payload(payload: &[u8]) -> Result<EncryptedData, Error> {
+            match *payload.first().ok_or(Error::NoEncodingPrefix)? {
+                ENCRYPTION_ELGAMAL_SECP256K1 => {}
+                unknown => return Err(Error::UnknownEncryption(unknown)),
+            }
+            let unblinding_key = payload
+                .get(1..1 + UNBLINDING_KEY_LEN)
+                .and_then(|key| PublicKey::from_slice(key).ok())
+                .ok_or(Error::InvalidUnblindingKey)?;
+            Ok(EncryptedData {
+                unblinding_key,
+                ciphertext: payload[1 + UNBLINDING_KEY_LEN..].to_vec(),
+            })
+        }
+    }
+
+    impl StrictEncode for EncryptedData {
+        fn strict_encode<E: io::Write>(
+            &self,
+            mut e: E,
+        ) -> Result<usize, strict_encoding::Error> {
+            let len = u32::try_from(self.ciphertext.len()).map_err(|_| {
+                strict_encoding::Error::ExceedMaxItems(self.ciphertext.len())
+            })?;
+            ENCRYPTION_ELGAMAL_SECP256K1.strict_encode(&mut e)?;
+            e.write_all(&self.unblinding_key.serialize())?;
+            // Ciphertext is not limited to 2^16 bytes of strict-encoded
+            // collections, so we use 32-bit length prefix
+            len.strict_encode(&mut e)?;
+            e.write_all(&self.ciphertext)?;
+            Ok(1 + UNBLINDING_KEY_LEN + 4 + self.ciphertext.len())
+        }
+    }
+
+    impl StrictDecode for EncryptedData {
+        fn strict_decode<D: io::Read>(
+            mut d: D,
+        ) -> Result<Self, strict_encoding::Error> {
+            match u8::strict_decode(&mut d)? {
+                ENCRYPTION_ELGAMAL_SECP256K1 => {}
+                unknown => {
+                    return Err(strict_encoding::Error::EnumValueNotKnown(
+                        "EncryptedData",
+                        unknown as usize,
+                    ))
+                }
+            }
+            let mut key = [0u8; UNBLINDING_KEY_LEN];
+            d.read_exact(&mut key)?;
+            let unblinding_key = PublicKey::from_slice(&key).map_err(|_| {
+                strict_encoding::Error::DataIntegrityError(s!(
+                    "invalid unblinding key"
+                ))
+            })?;
+            let len = u32::strict_decode(&mut d)? as u64;
+            let mut ciphertext = vec![];
+            d.take(len).read_to_end(&mut ciphertext)?;
+            if ciphertext.len() as u64 != len {
+                return Err(
+                    io::Error::from(io::ErrorKind::UnexpectedEof).into()
+                );
+            }
+            Ok(EncryptedData {
+                unblinding_key,
+                ciphertext,
+            })
+        }
+    }
+
+    impl Display for EncryptedData {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            let s = ::bech32::encode(
+                HRP_ENCRYPTED,
+                self.to_payload().to_base32(),
+                Variant::Bech32m,
+            )
+            .expect("HRP is hardcoded and can't fail");
+            f.write_str(&s)
+        }
+    }
+
+    impl FromStr for EncryptedData {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (hrp, data, variant) = ::bech32::decode(s)?;
+            if hrp != HRP_ENCRYPTED {
+                return Err(Error::WrongPrefix);
+            }
+            if variant != Variant::Bech32m {
+                return Err(Error::WrongVariant);
+            }
+            EncryptedData::from_payload(&Vec::<u8>::from_base32(&data)?)
+        }
+    }
+
+    /// Trait for creating `enc1...` (encrypted data) Bech32 representation of
+    /// a given type
+    pub trait ToBech32EncryptedString: StrictEncode + Sized {
+        /// Returns `enc1...` (encrypted data) Bech32 representation of a given
+        /// type, which can be decrypted only with the private key matching
+        /// `recipient` public key
+        fn to_bech32_encrypted_string(
+            &self,
+            recipient: PublicKey,
+        ) -> Result<String, Error> {
+            Ok(EncryptedData::encrypt(self, recipient)?.to_string())
+        }
+    }
+
+    impl<T> ToBech32EncryptedString for T where T: StrictEncode {}
+
+    /// Trait for reconstruction type data from `enc1...` (encrypted data)
+    /// Bech32 string
+    pub trait FromBech32EncryptedStr: StrictDecode {
+        /// Decrypts `enc1...` (encrypted data) Bech32 string with the
+        /// recipient private key and reconstructs type data from it
+        fn from_bech32_encrypted_str(
+            s: &str,
+            decryption_key: &SecretKey,
+        ) -> Result<Self, Error> {
+            EncryptedData::from_str(s)?.decrypt(decryption_key)
+        }
+    }
+
+    impl<T> FromBech32EncryptedStr for T where T: StrictDecode {}
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn encrypted_roundtrip() {
+            let decryption_key = SecretKey::new(&mut rand::thread_rng());
+            let recipient =
+                PublicKey::from_secret_key(SECP256K1, &decryption_key);
+
+            for len in [0usize, 1, 25, 26, 27, 28, 29, 30, 31, 100, 1000] {
+                let blob = Blob::from(vec![0xA5u8; len]);
+                let s = blob.to_bech32_encrypted_string(recipient).unwrap();
+                assert!(s.starts_with("enc1"));
+                assert_eq!(
+                    Blob::from_bech32_encrypted_str(&s, &decryption_key)
+                        .unwrap(),
+                    blob
+                );
+            }
+        }
+
+        #[test]
+        fn encrypted_data() {
+            let decryption_key = SecretKey::new(&mut rand::thread_rng());
+            let recipient =
+                PublicKey::from_secret_key(SECP256K1, &decryption_key);
+
+            let blob = Blob::from(b"confidential data".to_vec());
+            let encrypted = EncryptedData::encrypt(&blob, recipient).unwrap();
+            let s = encrypted.to_string();
+            assert_eq!(EncryptedData::from_str(&s), Ok(encrypted.clone()));
+            assert_eq!(
+                Blob::from_bech32_encrypted_str(&s, &decryption_key),
+                Ok(blob.clone())
+            );
+
+            let data = encrypted.strict_serialize().unwrap();
+            let decoded = EncryptedData::strict_deserialize(&data).unwrap();
+            assert_eq!(decoded, encrypted);
+            assert_eq!(decoded.decrypt::<Blob>(&decryption_key), Ok(blob));
+
+            let mut payload = encrypted.to_payload();
+            payload[0] = 2;
+            assert_eq!(
+                EncryptedData::from_payload(&payload),
+                Err(Error::UnknownEncryption(2))
+            );
+            assert_eq!(
+                EncryptedData::from_payload(&payload[..20]),
+                Err(Error::UnknownEncryption(2))
+            );
+            payload[0] = ENCRYPTION_ELGAMAL_SECP256K1;
+            assert_eq!(
+                EncryptedData::from_payload(&payload[..20]),
+                Err(Error::InvalidUnblindingKey)
+            );
+        }
+
+        #[test]
+        fn encrypted_large() {
+            let decryption_key = SecretKey::new(&mut rand::thread_rng());
+            let recipient =
+                PublicKey::from_secret_key(SECP256K1, &decryption_key);
+
+            let data = (0..70_000u32).map(|i| i as u8).collect::<Vec<_>>();
+            let encrypted =
+                EncryptedData::encrypt_bytes(&data, recipient).unwrap();
+            assert_eq!(encrypted.decrypt_bytes(&decryption_key), Ok(data));
+
+            let s = encrypted.to_string();
+            assert_eq!(EncryptedData::from_str(&s), Ok(encrypted.clone()));
+            let encoded = encrypted.strict_serialize().unwrap();
+            assert_eq!(
+                EncryptedData::strict_deserialize(&encoded).unwrap(),
+                encrypted
+            );
+            assert!(EncryptedData::strict_deserialize(
+                &encoded[..encoded.len() - 1]
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn encrypted_wrong_key() {
+            let decryption_key = SecretKey::new(&mut rand::thread_rng());
+            let other_key = SecretKey::new(&mut rand::thread_rng());
+            let recipient =
+                PublicKey::from_secret_key(SECP256K1, &decryption_key);
+
+            let blob = Blob::from(b"confidential data".to_vec());
+            let s = blob.to_bech32_encrypted_string(recipient).unwrap();
+            assert_ne!(
+                Blob::from_bech32_encrypted_str(&s, &other_key).ok(),
+                Some(blob)
+            );
+
+            let encrypted =
+                EncryptedData::encrypt_bytes(&[0xFF; 40], recipient).unwrap();
+            assert_ne!(
+                encrypted.decrypt_bytes(&other_key).ok(),
+                Some(vec![0xFF; 40])
+            );
+        }
+
+        #[test]
+        fn encrypted_wrong_prefix() {
+            let decryption_key = SecretKey::new(&mut rand::thread_rng());
+            let blob = Blob::from(b"public data".to_vec());
+            assert_eq!(
+                Blob::from_bech32_encrypted_str(
+                    &blob.bech32_data_string(),
+                    &decryption_key
+                )
+                .unwrap_err(),
+                Error::WrongPrefix
+            );
+        }
+    }
+}
+#[cfg(feature = "elgamal")]
+pub use encrypted::*;
+
+#[cfg(feature = "test-helpers")]
+pub mod test_helpers;
+
 /// Trait representing given bitcoin hash type as a Bech32 `id1...` value
 pub trait ToBech32IdString<Tag>
 where
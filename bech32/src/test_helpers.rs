@@ -0,0 +1,740 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Conformance kit for types implementing LNPBP-14 Bech32 representations.
+//!
+//! Each of the `test_*` functions runs a value through one of the Bech32
+//! conversion paths provided by this crate (strategy-based strings,
+//! `data1...`/`z1...` payload strings, `id1...` hash strings, `enc1...`
+//! encrypted strings and serde helpers), checking that the value survives
+//! the round trip and that malformed strings are rejected with the
+//! documented [`enum@Error`] variants. Errors are matched by their variant
+//! only, ignoring the details of the nested error types.
+//!
+//! The functions return [`Bech32TestFailure`] describing the first detected
+//! problem, so they can be used both in unit tests (with `unwrap`) and in
+//! property-based tests. [`generators`] module provides [`proptest`]
+//! strategies for producing the test inputs.
+//!
+//! The module is available with `test-helpers` feature.
+
+// Failures carry the complete errors for the diagnostics; the helpers are not
+// used in performance-sensitive code
+#![allow(clippy::result_large_err)]
+
+use std::fmt::Debug;
+use std::mem::discriminant;
+
+#[cfg(feature = "serde")]
+use serde_json::Value;
+#[cfg(feature = "elgamal")]
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use super::*;
+
+/// Failures detected by the Bech32 conformance tests
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum Bech32TestFailure {
+    /// bech32 string `{1}` does not use the expected HRP `{0}`
+    WrongHrp(String, String),
+
+    /// bech32 string `{0}` is not encoded with bech32m variant
+    NotBech32m(String),
+
+    /// unable to decode bech32 string `{0}`: {1}
+    DecodingFailed(String, Error),
+
+    /// the value decoded from bech32 string differs from the original one.
+    /// Original: {0}; decoded: {1}
+    RoundtripMismatch(String, String),
+
+    /// test case "{0}" must fail, but bech32 string `{1}` was successfully
+    /// decoded
+    UnexpectedSuccess(&'static str, String),
+
+    /// test case "{0}" must fail with error `{1:?}`, but it failed with
+    /// `{2:?}` instead
+    WrongError(&'static str, Error, Error),
+
+    /// unable to encrypt value {0}: {1}
+    #[cfg(feature = "elgamal")]
+    EncryptionFailed(String, Error),
+
+    /// encrypted bech32 string `{0}` was decrypted into the original value
+    /// using a wrong decryption key
+    #[cfg(feature = "elgamal")]
+    WrongKeyDecryption(String),
+
+    /// serde conformance failure: {0}
+    #[cfg(feature = "serde")]
+    Serde(String),
+}
+
+/// Strategies which are able to produce malformed payloads for the
+/// conformance tests. Implemented for all [`strategies`] except
+/// [`strategies::Wrapped`], which types must be tested through the wrapped
+/// inner type.
+pub trait StrategyConformance {
+    /// Produces malformed variants of a valid Bech32 `payload` of type `T`,
+    /// each accompanied by the test case name and the expected decoding error
+    fn malformed_payloads<T: Strategy>(
+        payload: &[u8],
+    ) -> Vec<(&'static str, Vec<u8>, Error)>;
+}
+
+impl StrategyConformance for strategies::UsingStrictEncoding {
+    fn malformed_payloads<T: Strategy>(
+        payload: &[u8],
+    ) -> Vec<(&'static str, Vec<u8>, Error)> {
+        match payload.split_last() {
+            Some((_, truncated)) => vec![(
+                "truncated data",
+                truncated.to_vec(),
+                Error::NotStrictEncoded(
+                    strict_encoding::Error::DataIntegrityError(s!("")),
+                ),
+            )],
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(feature = "zip")]
+impl StrategyConformance for strategies::CompressedStrictEncoding {
+    fn malformed_payloads<T: Strategy>(
+        payload: &[u8],
+    ) -> Vec<(&'static str, Vec<u8>, Error)> {
+        let dictionary = T::ZIP_DICTIONARY;
+        let mut cases = zip_malformed_payloads();
+
+        let unknown_id = dictionary
+            .map(|dictionary| dictionary.id() % ZIP_DICTIONARY_ID_MAX + 1)
+            .unwrap_or(1);
+        cases.push((
+            "unknown dictionary",
+            vec![unknown_id << 4 | RAW_DATA_ENCODING_DEFLATE, 0x03, 0x00],
+            Error::UnknownZipDictionary(unknown_id),
+        ));
+
+        if let Some(mut data) =
+            zip::inflate_payload(payload, dictionary.as_ref())
+                .ok()
+                .filter(|data| !data.is_empty())
+        {
+            data.pop();
+            cases.push((
+                "truncated data",
+                zip::deflate_payload(&data, dictionary.as_ref()),
+                Error::NotStrictEncoded(
+                    strict_encoding::Error::DataIntegrityError(s!("")),
+                ),
+            ));
+        }
+        cases
+    }
+}
+
+#[cfg(feature = "zip")]
+fn zip_malformed_payloads() -> Vec<(&'static str, Vec<u8>, Error)> {
+    vec![
+        ("no encoding prefix", vec![], Error::NoEncodingPrefix),
+        (
+            "unknown encoding",
+            vec![0xFF],
+            Error::UnknownRawDataEncoding(0xFF),
+        ),
+        (
+            "corrupted deflate stream",
+            vec![RAW_DATA_ENCODING_DEFLATE, 0xFF, 0xFF, 0xFF],
+            Error::InflateError(s!("")),
+        ),
+    ]
+}
+
+fn encode(hrp: &str, payload: &[u8], variant: Variant) -> String {
+    ::bech32::encode(hrp, payload.to_base32(), variant)
+        .expect("HRP is checked by the caller")
+}
+
+fn decode_payload(hrp: &str, s: &str) -> Result<Vec<u8>, Bech32TestFailure> {
+    let (prefix, data, variant) = ::bech32::decode(s).map_err(|err| {
+        Bech32TestFailure::DecodingFailed(s.to_owned(), err.into())
+    })?;
+    if prefix != hrp {
+        return Err(Bech32TestFailure::WrongHrp(hrp.to_owned(), s.to_owned()));
+    }
+    if variant != Variant::Bech32m {
+        return Err(Bech32TestFailure::NotBech32m(s.to_owned()));
+    }
+    Vec::<u8>::from_base32(&data).map_err(|err| {
+        Bech32TestFailure::DecodingFailed(s.to_owned(), err.into())
+    })
+}
+
+fn expect_error<T>(
+    case: &'static str,
+    s: &str,
+    result: Result<T, Error>,
+    expected: Error,
+) -> Result<(), Bech32TestFailure> {
+    match result {
+        Ok(_) => Err(Bech32TestFailure::UnexpectedSuccess(case, s.to_owned())),
+        Err(err) if discriminant(&err) == discriminant(&expected) => Ok(()),
+        Err(err) => Err(Bech32TestFailure::WrongError(case, expected, err)),
+    }
+}
+
+fn expect_roundtrip<T>(
+    original: &T,
+    s: &str,
+    decoded: Result<T, Error>,
+) -> Result<(), Bech32TestFailure>
+where
+    T: Eq + Debug,
+{
+    let decoded = decoded
+        .map_err(|err| Bech32TestFailure::DecodingFailed(s.to_owned(), err))?;
+    if &decoded != original {
+        return Err(Bech32TestFailure::RoundtripMismatch(
+            format!("{:?}", original),
+            format!("{:?}", decoded),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a valid string `s` with a given `hrp` is decoded by `decode`
+/// into `original` (also when upper-cased) and that errors common for all
+/// Bech32 string types are reported: [`Error::Bech32Error`] for corrupted
+/// strings, [`Error::WrongPrefix`] and [`Error::WrongVariant`].
+fn check_string<T>(
+    original: &T,
+    hrp: &str,
+    s: &str,
+    decode: impl Fn(&str) -> Result<T, Error>,
+) -> Result<Vec<u8>, Bech32TestFailure>
+where
+    T: Eq + Debug,
+{
+    let payload = decode_payload(hrp, s)?;
+    expect_roundtrip(original, s, decode(s))?;
+    let upper = s.to_uppercase();
+    expect_roundtrip(original, &upper, decode(&upper))?;
+
+    let mut corrupted = s.to_owned();
+    let last = corrupted.pop().expect("bech32 string can't be empty");
+    corrupted.push(if last == 'q' { 'p' } else { 'q' });
+    expect_error(
+        "corrupted checksum",
+        &corrupted,
+        decode(&corrupted),
+        Error::Bech32Error(::bech32::Error::InvalidChecksum),
+    )?;
+
+    let mut mixed = s.to_owned();
+    let pos = mixed
+        .find(|c: char| c.is_ascii_lowercase())
+        .expect("bech32 string always contains letters");
+    mixed.replace_range(pos..=pos, &mixed[pos..=pos].to_uppercase());
+    expect_error(
+        "mixed case",
+        &mixed,
+        decode(&mixed),
+        Error::Bech32Error(::bech32::Error::MixedCase),
+    )?;
+
+    let other_hrp = if hrp == "wrong" { "other" } else { "wrong" };
+    let wrong = encode(other_hrp, &payload, Variant::Bech32m);
+    expect_error("wrong HRP", &wrong, decode(&wrong), Error::WrongPrefix)?;
+
+    let legacy = encode(hrp, &payload, Variant::Bech32);
+    expect_error(
+        "legacy bech32 variant",
+        &legacy,
+        decode(&legacy),
+        Error::WrongVariant,
+    )?;
+
+    Ok(payload)
+}
+
+fn check_malformed<T>(
+    hrp: &str,
+    cases: Vec<(&'static str, Vec<u8>, Error)>,
+    decode: impl Fn(&str) -> Result<T, Error>,
+) -> Result<(), Bech32TestFailure> {
+    for (case, payload, expected) in cases {
+        let s = encode(hrp, &payload, Variant::Bech32m);
+        expect_error(case, &s, decode(&s), expected)?;
+    }
+    Ok(())
+}
+
+/// Tests [`ToBech32String`] and [`FromBech32Str`] implementations of a type.
+/// Checks that the produced string uses bech32m variant with the type HRP,
+/// decodes back to the same value and that malformed strings are rejected
+/// with errors common to all Bech32 string types. Returns the tested string.
+pub fn test_bech32_string<T>(object: &T) -> Result<String, Bech32TestFailure>
+where
+    T: ToBech32String + FromBech32Str + Eq + Debug,
+{
+    let s = object.to_bech32_string();
+    check_string(object, T::HRP, &s, T::from_bech32_str)?;
+    Ok(s)
+}
+
+/// Tests Bech32 implementation of a type provided by [`Strategy`] trait. In
+/// addition to the checks performed by [`test_bech32_string`], verifies that
+/// malformed strategy-specific payloads are rejected with errors documented
+/// for the used strategy. Returns the tested string.
+pub fn test_strategy<T>(object: &T) -> Result<String, Bech32TestFailure>
+where
+    T: Strategy + ToBech32String + FromBech32Str + Eq + Debug,
+    T::Strategy: StrategyConformance,
+{
+    let hrp = <T as FromBech32Str>::HRP;
+    let s = object.to_bech32_string();
+    let payload = check_string(object, hrp, &s, T::from_bech32_str)?;
+    check_malformed(
+        hrp,
+        T::Strategy::malformed_payloads::<T>(&payload),
+        T::from_bech32_str,
+    )?;
+    Ok(s)
+}
+
+/// Tests `data1...` representation of a type provided by
+/// [`ToBech32DataString`] and [`FromBech32DataStr`] and, with `zip` feature,
+/// `z1...` representation provided by [`ToBech32ZipString`] and
+/// [`FromBech32ZipStr`].
+pub fn test_data_strings<T>(object: &T) -> Result<(), Bech32TestFailure>
+where
+    T: ToBech32Payload + FromBech32Payload + Eq + Debug,
+{
+    check_data_strings(
+        object,
+        object.to_bech32_data_string(),
+        #[cfg(feature = "zip")]
+        object.to_bech32_zip_string(),
+    )
+}
+
+/// Tests `data1...` representation of a type provided by
+/// [`Bech32DataString`] and [`FromBech32DataStr`] and, with `zip` feature,
+/// `z1...` representation provided by [`Bech32ZipString`] and
+/// [`FromBech32ZipStr`].
+pub fn test_data_slice_strings<T>(object: &T) -> Result<(), Bech32TestFailure>
+where
+    T: AsBech32Payload + FromBech32Payload + Eq + Debug,
+{
+    check_data_strings(
+        object,
+        object.bech32_data_string(),
+        #[cfg(feature = "zip")]
+        object.bech32_zip_string(),
+    )
+}
+
+fn check_data_strings<T>(
+    object: &T,
+    data_string: String,
+    #[cfg(feature = "zip")] zip_string: String,
+) -> Result<(), Bech32TestFailure>
+where
+    T: FromBech32Payload + Eq + Debug,
+{
+    check_string(object, HRP_DATA, &data_string, T::from_bech32_data_str)?;
+
+    #[cfg(feature = "zip")]
+    {
+        check_string(object, HRP_ZIP, &zip_string, T::from_bech32_zip_str)?;
+        check_malformed(HRP_ZIP, zip_malformed_payloads(), |s| {
+            T::from_bech32_zip_str(s)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Tests `id1...` representation of a hash type provided by
+/// [`ToBech32IdString`] and [`FromBech32IdStr`]. Returns the tested string.
+pub fn test_id_string<T, Tag>(id: &T) -> Result<String, Bech32TestFailure>
+where
+    T: ToBech32IdString<Tag> + FromBech32IdStr<Tag> + Eq + Debug,
+    Tag: sha256t::Tag,
+{
+    let s = id.to_bech32_id_string();
+    let payload = check_string(id, HRP_ID, &s, T::from_bech32_id_str)?;
+    check_malformed(
+        HRP_ID,
+        vec![(
+            "truncated hash",
+            payload[1..].to_vec(),
+            Error::NotBitcoinHash(bitcoin_hashes::Error::InvalidLength(
+                32,
+                payload.len() - 1,
+            )),
+        )],
+        T::from_bech32_id_str,
+    )?;
+    Ok(s)
+}
+
+/// Tests `enc1...` representation of a type provided by
+/// [`ToBech32EncryptedString`] and [`FromBech32EncryptedStr`], using
+/// randomly generated recipient key. Returns the tested string.
+#[cfg(feature = "elgamal")]
+pub fn test_encrypted_string<T>(object: &T) -> Result<String, Bech32TestFailure>
+where
+    T: StrictEncode + StrictDecode + Eq + Debug,
+{
+    use secp256k1::{rand, PublicKey, SecretKey, SECP256K1};
+
+    let key = SecretKey::new(&mut rand::thread_rng());
+    let recipient = PublicKey::from_secret_key(SECP256K1, &key);
+    let decode = |s: &str| T::from_bech32_encrypted_str(s, &key);
+
+    let s = object
+        .to_bech32_encrypted_string(recipient)
+        .map_err(|err| {
+            Bech32TestFailure::EncryptionFailed(format!("{:?}", object), err)
+        })?;
+    let payload = check_string(object, HRP_ENCRYPTED, &s, decode)?;
+
+    let wrong_key = SecretKey::new(&mut rand::thread_rng());
+    if T::from_bech32_encrypted_str(&s, &wrong_key).as_ref() == Ok(object) {
+        return Err(Bech32TestFailure::WrongKeyDecryption(s));
+    }
+
+    let mut invalid_key = payload.clone();
+    invalid_key[1..34].copy_from_slice(&[0u8; 33]);
+    check_malformed(
+        HRP_ENCRYPTED,
+        vec![
+            ("no encryption prefix", vec![], Error::NoEncodingPrefix),
+            (
+                "unknown encryption",
+                vec![0xFF],
+                Error::UnknownEncryption(0xFF),
+            ),
+            (
+                "invalid unblinding key",
+                invalid_key,
+                Error::InvalidUnblindingKey,
+            ),
+            (
+                "truncated ciphertext",
+                payload[..payload.len() - 1].to_vec(),
+                Error::Encryption(lnpbp_elgamal::Error::UnpaddedLength),
+            ),
+        ],
+        decode,
+    )?;
+    Ok(s)
+}
+
+/// Tests serde [`serialize`] and [`deserialize`] helpers for a type,
+/// checking that the value is serialized as its Bech32 string, deserialized
+/// back to the same value and that invalid strings are rejected.
+#[cfg(feature = "serde")]
+pub fn test_serde<T>(object: &T) -> Result<(), Bech32TestFailure>
+where
+    T: ToBech32String + FromBech32Str + Eq + Debug,
+{
+    let s = object.to_bech32_string();
+    let value = serialize(object, serde_json::value::Serializer)
+        .map_err(|err| Bech32TestFailure::Serde(err.to_string()))?;
+    if value != Value::String(s.clone()) {
+        return Err(Bech32TestFailure::Serde(format!(
+            "value is serialized as {} instead of bech32 string `{}`",
+            value, s
+        )));
+    }
+
+    let decoded: T = deserialize(value)
+        .map_err(|err| Bech32TestFailure::Serde(err.to_string()))?;
+    expect_roundtrip(object, &s, Ok(decoded))?;
+
+    let mut corrupted = s;
+    corrupted.pop();
+    if deserialize::<T, _>(Value::String(corrupted.clone())).is_ok() {
+        return Err(Bech32TestFailure::Serde(format!(
+            "corrupted bech32 string `{}` was deserialized",
+            corrupted
+        )));
+    }
+    if deserialize::<T, _>(Value::Null).is_ok() {
+        return Err(Bech32TestFailure::Serde(s!(
+            "non-string value was deserialized"
+        )));
+    }
+    Ok(())
+}
+
+/// Runs the provided conformance `test` against values generated by
+/// `strategy`, panicking with the minimal failing value on failure.
+pub fn proptest_conformance<T, R>(
+    strategy: impl proptest::strategy::Strategy<Value = T>,
+    test: impl Fn(&T) -> Result<R, Bech32TestFailure>,
+) where
+    T: Debug,
+{
+    use proptest::test_runner::{TestCaseError, TestRunner};
+
+    TestRunner::default()
+        .run(&strategy, |object| {
+            test(&object)
+                .map(|_| ())
+                .map_err(|err| TestCaseError::fail(err.to_string()))
+        })
+        .unwrap_or_else(|err| panic!("{}", err));
+}
+
+/// Checks that decoding arbitrary and malformed strings with the type HRP
+/// never panics and never succeeds for strings not using bech32m
+/// encoding with the type HRP.
+pub fn proptest_decoding<T>()
+where
+    T: FromBech32Str + Debug,
+{
+    use proptest::test_runner::{TestCaseError, TestRunner};
+
+    let strings = proptest::prop_oneof![
+        generators::malformed_bech32_string(T::HRP),
+        generators::legacy_bech32_string(T::HRP, 256),
+        proptest::arbitrary::any::<String>(),
+    ];
+    TestRunner::default()
+        .run(&strings, |s| match T::from_bech32_str(&s) {
+            Ok(decoded) if decode_payload(T::HRP, &s).is_err() => {
+                Err(TestCaseError::fail(format!(
+                    "string `{}` is decoded as {:?}",
+                    s, decoded
+                )))
+            }
+            _ => Ok(()),
+        })
+        .unwrap_or_else(|err| panic!("{}", err));
+}
+
+pub mod generators {
+    //! [`proptest`] strategies generating inputs for the Bech32 conformance
+    //! tests.
+
+    use proptest::arbitrary::any;
+    use proptest::collection::vec;
+    use proptest::strategy::Strategy;
+    use proptest::string::string_regex;
+
+    use super::*;
+
+    /// Generates payloads of up to `max_len` bytes
+    pub fn payload(max_len: usize) -> impl Strategy<Value = Vec<u8>> {
+        vec(any::<u8>(), 0..=max_len)
+    }
+
+    /// Generates [`Blob`]s of up to `max_len` bytes
+    pub fn blob(max_len: usize) -> impl Strategy<Value = Blob> {
+        payload(max_len).prop_map(Blob::from)
+    }
+
+    /// Generates valid Bech32 HRPs
+    pub fn hrp() -> impl Strategy<Value = String> {
+        string_regex("[a-z][a-z0-9]{0,9}").expect("hardcoded regex")
+    }
+
+    /// Generates valid bech32m strings with a given `hrp` and payloads of up
+    /// to `max_len` bytes
+    pub fn bech32m_string(
+        hrp: &'static str,
+        max_len: usize,
+    ) -> impl Strategy<Value = String> {
+        payload(max_len)
+            .prop_map(move |data| encode(hrp, &data, Variant::Bech32m))
+    }
+
+    /// Generates valid strings with a given `hrp` and payloads of up to
+    /// `max_len` bytes, which are encoded with legacy bech32 variant
+    pub fn legacy_bech32_string(
+        hrp: &'static str,
+        max_len: usize,
+    ) -> impl Strategy<Value = String> {
+        payload(max_len)
+            .prop_map(move |data| encode(hrp, &data, Variant::Bech32))
+    }
+
+    /// Generates strings with a given `hrp` followed by random data
+    /// characters, which in most cases have invalid checksum
+    pub fn malformed_bech32_string(
+        hrp: &'static str,
+    ) -> impl Strategy<Value = String> {
+        string_regex("[qpzry9x8gf2tvdw0s3jn54khce6mua7l]{0,128}")
+            .expect("hardcoded regex")
+            .prop_map(move |data| format!("{}1{}", hrp, data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use amplify::Wrapper;
+    use bitcoin_hashes::sha256;
+    use proptest::strategy::Strategy as _;
+
+    use super::*;
+
+    #[derive(Clone, PartialEq, Eq, Debug, StrictEncode, StrictDecode)]
+    struct Sample {
+        name: String,
+        data: Vec<u8>,
+    }
+
+    impl Strategy for Sample {
+        const HRP: &'static str = "sample";
+        type Strategy = strategies::UsingStrictEncoding;
+    }
+
+    #[cfg(feature = "zip")]
+    #[derive(Clone, PartialEq, Eq, Debug, StrictEncode, StrictDecode)]
+    struct ZipSample(Vec<u8>);
+
+    #[cfg(feature = "zip")]
+    impl Strategy for ZipSample {
+        const HRP: &'static str = "zsample";
+        type Strategy = strategies::CompressedStrictEncoding;
+    }
+
+    #[cfg(feature = "zip")]
+    #[derive(Clone, PartialEq, Eq, Debug, StrictEncode, StrictDecode)]
+    struct DictSample(String);
+
+    #[cfg(feature = "zip")]
+    impl Strategy for DictSample {
+        const HRP: &'static str = "dsample";
+        type Strategy = strategies::CompressedStrictEncoding;
+        const ZIP_DICTIONARY: Option<ZipDictionary> =
+            Some(ZipDictionary::with(15, b"lorem ipsum dolor sit amet"));
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug, From)]
+    struct Payload(Vec<u8>);
+
+    impl ToBech32Payload for Payload {
+        fn to_bech32_payload(&self) -> Vec<u8> { self.0.clone() }
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    struct TestTag;
+
+    impl sha256t::Tag for TestTag {
+        fn engine() -> sha256::HashEngine { sha256::HashEngine::default() }
+    }
+
+    #[derive(Wrapper, Clone, PartialEq, Eq, Debug, From)]
+    struct TestId(sha256t::Hash<TestTag>);
+
+    fn samples() -> impl proptest::strategy::Strategy<Value = Sample> {
+        ("[a-z ]{0,16}", generators::payload(128))
+            .prop_map(|(name, data)| Sample { name, data })
+    }
+
+    #[test]
+    fn strategy() {
+        let sample = Sample {
+            name: s!("sample"),
+            data: vec![0xA5; 32],
+        };
+        assert!(test_strategy(&sample).unwrap().starts_with("sample1"));
+        test_bech32_string(&sample).unwrap();
+        proptest_conformance(samples(), test_strategy);
+        proptest_decoding::<Sample>();
+    }
+
+    #[test]
+    #[cfg(feature = "zip")]
+    fn compressed_strategy() {
+        let sample = ZipSample(vec![0xA5; 1024]);
+        assert!(test_strategy(&sample).unwrap().starts_with("zsample1"));
+        proptest_conformance(
+            generators::payload(256).prop_map(ZipSample),
+            test_strategy,
+        );
+        proptest_decoding::<ZipSample>();
+    }
+
+    #[test]
+    #[cfg(feature = "zip")]
+    fn compressed_strategy_dictionary() {
+        let sample = DictSample(s!("lorem ipsum dolor sit amet lorem"));
+        assert!(test_strategy(&sample).unwrap().starts_with("dsample1"));
+        proptest_conformance(
+            "(lorem|ipsum|dolor|sit|amet| ){0,32}".prop_map(DictSample),
+            test_strategy,
+        );
+        proptest_decoding::<DictSample>();
+    }
+
+    #[test]
+    fn data_strings() {
+        test_data_slice_strings(&Blob::from(vec![])).unwrap();
+        proptest_conformance(generators::blob(256), test_data_slice_strings);
+        proptest_conformance(
+            generators::payload(256).prop_map(Payload),
+            test_data_strings,
+        );
+    }
+
+    #[test]
+    fn id_string() {
+        let id = TestId::from_inner(sha256t::Hash::hash(b"test"));
+        assert!(test_id_string(&id).unwrap().starts_with("id1"));
+    }
+
+    #[test]
+    #[cfg(feature = "elgamal")]
+    fn encrypted_string() {
+        test_encrypted_string(&Blob::from(vec![])).unwrap();
+        proptest_conformance(samples(), test_encrypted_string);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_helpers() { proptest_conformance(samples(), test_serde); }
+
+    #[test]
+    fn failures() {
+        #[derive(Clone, PartialEq, Eq, Debug, StrictEncode, StrictDecode)]
+        struct Lax(Vec<u8>);
+
+        impl ToBech32String for Lax {
+            fn to_bech32_string(&self) -> String {
+                encode(Self::HRP, &self.0, Variant::Bech32)
+            }
+        }
+
+        impl FromBech32Str for Lax {
+            const HRP: &'static str = "lax";
+
+            fn from_bech32_str(s: &str) -> Result<Self, Error> {
+                let (_, data, _) = ::bech32::decode(s)?;
+                Ok(Lax(Vec::<u8>::from_base32(&data)?))
+            }
+        }
+
+        let lax = Lax(vec![1, 2, 3]);
+        assert_eq!(
+            test_bech32_string(&lax).unwrap_err(),
+            Bech32TestFailure::NotBech32m(lax.to_bech32_string())
+        );
+    }
+}
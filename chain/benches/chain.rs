@@ -0,0 +1,106 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Benchmarks comparing `Chain` comparison and hashing with the same
+//! operations performed over cloned chain parameters (which is how they were
+//! implemented before).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use bitcoin::hashes::hex::FromHex;
+use bitcoin::Script;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lnpbp_chain::Chain;
+
+fn chains() -> Vec<Chain> {
+    let mut chains = Chain::all_standard().to_vec();
+    chains.push(Chain::SignetChallenge(Script::from_hex("51").unwrap()));
+    chains.push(Chain::Other(Box::new(Chain::Mainnet.chain_params())));
+    chains
+}
+
+fn eq(c: &mut Criterion) {
+    let chains = chains();
+    let mut group = c.benchmark_group("eq");
+    group.bench_function("chain_params", |b| {
+        b.iter(|| {
+            for a in &chains {
+                for b in &chains {
+                    black_box(a.chain_params() == b.chain_params());
+                }
+            }
+        })
+    });
+    group.bench_function("chain", |b| {
+        b.iter(|| {
+            for a in &chains {
+                for b in &chains {
+                    black_box(a == b);
+                }
+            }
+        })
+    });
+    group.finish();
+}
+
+fn sort(c: &mut Criterion) {
+    let chains = (0..8).flat_map(|_| chains()).collect::<Vec<_>>();
+    let mut group = c.benchmark_group("sort");
+    group.bench_function("chain_params", |b| {
+        b.iter(|| {
+            let mut chains = chains.clone();
+            chains.sort_by_key(Chain::chain_params);
+            black_box(chains)
+        })
+    });
+    group.bench_function("chain", |b| {
+        b.iter(|| {
+            let mut chains = chains.clone();
+            chains.sort();
+            black_box(chains)
+        })
+    });
+    group.finish();
+}
+
+fn hash(c: &mut Criterion) {
+    let chains = chains();
+    let mut group = c.benchmark_group("hash");
+    group.bench_function("chain_params", |b| {
+        b.iter(|| {
+            for chain in &chains {
+                let mut hasher = DefaultHasher::new();
+                chain.chain_params().hash(&mut hasher);
+                black_box(hasher.finish());
+            }
+        })
+    });
+    group.bench_function("chain", |b| {
+        b.iter(|| {
+            for chain in &chains {
+                let mut hasher = DefaultHasher::new();
+                chain.hash(&mut hasher);
+                black_box(hasher.finish());
+            }
+        })
+    });
+    group.bench_function("hash_set", |b| {
+        b.iter(|| black_box(chains.iter().collect::<HashSet<_>>()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, eq, sort, hash);
+criterion_main!(benches);
@@ -0,0 +1,581 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Address prefixes used by chains and detection of the chain from an address
+//! string.
+
+use bitcoin::bech32::{self, FromBase32, Variant};
+use bitcoin::util::base58;
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::{Chain, ChainParams};
+
+/// Length of the data in base58 P2PKH and P2SH addresses, including version
+/// byte
+const BASE58_ADDRESS_LEN: usize = 21;
+
+/// Length of the data in base58 confidential addresses, including
+/// confidential prefix, version byte and 33-byte blinding public key
+const BASE58_CONFIDENTIAL_LEN: usize = 55;
+
+/// Characters allowed in the data part of bech32 and blech32 strings
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Length of the blinding public key prefixing witness program in blech32
+/// confidential addresses
+const BLINDING_KEY_LEN: usize = 33;
+
+/// Number of characters in blech32 checksum
+const BLECH32_CHECKSUM_LEN: usize = 12;
+
+/// Generator of blech32 checksum, as defined by Elements
+const BLECH32_GEN: [u64; 5] = [
+    0x7d52fba40bd886,
+    0x5e8dbf1a03950c,
+    0x1c3a3c74072a18,
+    0x385d72fa0e5139,
+    0x7093e5a608865b,
+];
+
+/// Checksum constant of blech32 variant, used by witness v0 addresses
+const BLECH32_CONST: u64 = 1;
+
+/// Checksum constant of blech32m variant, used by witness v1+ addresses
+const BLECH32M_CONST: u64 = 0x0455972a3350f7a1;
+
+/// Address prefixes used by a chain, complementing BIP 173 HRP
+/// ([`ChainParams::bip173_prefix`])
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[derive(StrictEncode, StrictDecode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+pub struct AddressPrefixes {
+    /// Base58 version byte for P2PKH addresses
+    pub p2pkh: u8,
+
+    /// Base58 version byte for P2SH addresses
+    pub p2sh: u8,
+
+    /// Base58 prefix byte for confidential (blinded) addresses, used by
+    /// chains with [`crate::ChainFormat::Elements`] format. HRP of blech32
+    /// confidential addresses is defined by
+    /// [`crate::ElementsParams::blinded_hrp`]
+    pub confidential: Option<u8>,
+}
+
+impl AddressPrefixes {
+    /// Constructs address prefixes for a bitcoin-format chain without
+    /// confidential addresses
+    pub fn with(p2pkh: u8, p2sh: u8) -> AddressPrefixes {
+        AddressPrefixes {
+            p2pkh,
+            p2sh,
+            confidential: None,
+        }
+    }
+
+    /// Address prefixes used by bitcoin mainnet
+    pub fn mainnet() -> AddressPrefixes { AddressPrefixes::with(0, 5) }
+
+    /// Address prefixes used by bitcoin testnets, signets and regtest
+    pub fn testnet() -> AddressPrefixes { AddressPrefixes::with(111, 196) }
+}
+
+/// Format of an address string
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Display, Hash)]
+#[display(Debug)]
+pub enum AddressFormat {
+    /// Base58 pay-to-public-key-hash address
+    P2pkh,
+
+    /// Base58 pay-to-script-hash address
+    P2sh,
+
+    /// Bech32 (for witness version 0) or bech32m (for witness versions 1 to
+    /// 16) segwit address
+    Segwit,
+
+    /// Base58 confidential (blinded) address
+    ConfidentialBase58,
+
+    /// Blech32 confidential (blinded) segwit address
+    ConfidentialBlech32,
+}
+
+/// Errors parsing and validating addresses
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum AddressError {
+    /// invalid base58 address encoding: {0}
+    Base58(base58::Error),
+
+    /// invalid bech32 address encoding: {0}
+    Bech32(bech32::Error),
+
+    /// address data have invalid length of {0} bytes
+    InvalidLength(usize),
+
+    /// segwit address has invalid witness version or program length
+    InvalidWitnessProgram,
+
+    /// segwit address uses wrong bech32 variant for its witness version
+    WrongVariant,
+
+    /// address prefix is not used by any known chain
+    UnknownPrefix,
+
+    /// address prefix does not match the chain
+    ChainMismatch,
+}
+
+/// Address prefix extracted from the address string
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Prefix {
+    Base58(u8),
+    ConfidentialBase58(u8, u8),
+    Hrp(String),
+}
+
+/// Computes blech32 checksum polynomial over HRP and data characters
+fn blech32_polymod(hrp: &str, data: &[u8]) -> u64 {
+    let values = hrp
+        .bytes()
+        .map(|b| b >> 5)
+        .chain(Some(0))
+        .chain(hrp.bytes().map(|b| b & 0x1f))
+        .chain(data.iter().copied());
+    let mut chk = 1u64;
+    for value in values {
+        let top = chk >> 55;
+        chk = ((chk & 0x7fffffffffffff) << 5) ^ value as u64;
+        for (i, gen) in BLECH32_GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= gen;
+            }
+        }
+    }
+    chk
+}
+
+/// Decodes data part of a lowercase blech32 string, verifying its checksum
+/// and witness program
+fn blech32_decode(hrp: &str, data: &str) -> Result<(), AddressError> {
+    let data = data
+        .chars()
+        .map(|c| {
+            BECH32_CHARSET
+                .find(c)
+                .map(|pos| pos as u8)
+                .ok_or(AddressError::Bech32(bech32::Error::InvalidChar(c)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if data.len() <= BLECH32_CHECKSUM_LEN {
+        return Err(AddressError::Bech32(bech32::Error::InvalidLength));
+    }
+    let variant = match blech32_polymod(hrp, &data) {
+        BLECH32_CONST => Variant::Bech32,
+        BLECH32M_CONST => Variant::Bech32m,
+        _ => return Err(AddressError::Bech32(bech32::Error::InvalidChecksum)),
+    };
+
+    let data = &data[..data.len() - BLECH32_CHECKSUM_LEN];
+    let (version, program) = data
+        .split_first()
+        .ok_or(AddressError::InvalidWitnessProgram)?;
+    let program = program
+        .iter()
+        .map(|v| bech32::u5::try_from_u8(*v).expect("charset has 32 chars"))
+        .collect::<Vec<_>>();
+    let program =
+        Vec::<u8>::from_base32(&program).map_err(AddressError::Bech32)?;
+    let len = program.len().saturating_sub(BLINDING_KEY_LEN);
+    match (*version, variant, len) {
+        (0, Variant::Bech32, 20 | 32) => Ok(()),
+        (1..=16, Variant::Bech32m, 2..=40) => Ok(()),
+        (0, Variant::Bech32m, _) | (1..=16, Variant::Bech32, _) => {
+            Err(AddressError::WrongVariant)
+        }
+        _ => Err(AddressError::InvalidWitnessProgram),
+    }
+}
+
+/// Parses address string without matching it to a chain. Blech32 addresses
+/// are recognized by their HRP, which must be one of the `blech32_hrps`
+fn parse_address<'hrp>(
+    s: &str,
+    mut blech32_hrps: impl Iterator<Item = &'hrp str>,
+) -> Result<(AddressFormat, Prefix), AddressError> {
+    let bech32_err = match bech32::decode(s) {
+        Ok((hrp, data, variant)) => {
+            let (version, program) = data
+                .split_first()
+                .ok_or(AddressError::InvalidWitnessProgram)?;
+            let program = Vec::<u8>::from_base32(program)
+                .map_err(AddressError::Bech32)?;
+            match (version.to_u8(), variant, program.len()) {
+                (0, Variant::Bech32, 20 | 32) => {}
+                (1..=16, Variant::Bech32m, 2..=40) => {}
+                (0, Variant::Bech32m, _) | (1..=16, Variant::Bech32, _) => {
+                    return Err(AddressError::WrongVariant)
+                }
+                _ => return Err(AddressError::InvalidWitnessProgram),
+            }
+            return Ok((AddressFormat::Segwit, Prefix::Hrp(hrp)));
+        }
+        Err(err) => err,
+    };
+
+    let base58_err = match base58::from_check(s) {
+        Ok(data) => {
+            return match data.len() {
+                BASE58_ADDRESS_LEN => {
+                    Ok((AddressFormat::P2pkh, Prefix::Base58(data[0])))
+                }
+                BASE58_CONFIDENTIAL_LEN => Ok((
+                    AddressFormat::ConfidentialBase58,
+                    Prefix::ConfidentialBase58(data[0], data[1]),
+                )),
+                len => Err(AddressError::InvalidLength(len)),
+            }
+        }
+        Err(err) => err,
+    };
+
+    // Blech32 uses longer checksum, which is not supported by bech32 library
+    let lower = s.to_lowercase();
+    if s == lower || s == s.to_uppercase() {
+        if let Some((hrp, data)) = lower.rsplit_once('1') {
+            if blech32_hrps.any(|known| known == hrp) {
+                blech32_decode(hrp, data)?;
+                return Ok((
+                    AddressFormat::ConfidentialBlech32,
+                    Prefix::Hrp(hrp.to_owned()),
+                ));
+            }
+        }
+    }
+
+    // Base58 strings are case-sensitive, while bech32 ones must use a single
+    // case and have non-empty HRP, so we report error of the encoding the
+    // string looks like
+    let single_case = s == lower || s == s.to_uppercase();
+    match (s.rfind('1'), bech32_err) {
+        (Some(pos), err) if pos > 0 && single_case => {
+            Err(AddressError::Bech32(err))
+        }
+        _ => Err(AddressError::Base58(base58_err)),
+    }
+}
+
+impl ChainParams {
+    /// Checks that the address belongs to the chain, returning its format
+    pub fn check_address(
+        &self,
+        s: &str,
+    ) -> Result<AddressFormat, AddressError> {
+        let (format, prefix) =
+            parse_address(s, self.blinded_hrp().into_iter())?;
+        self.match_prefix(format, &prefix)
+    }
+
+    fn match_prefix(
+        &self,
+        format: AddressFormat,
+        prefix: &Prefix,
+    ) -> Result<AddressFormat, AddressError> {
+        let prefixes = &self.address_prefixes;
+        match (format, prefix) {
+            (AddressFormat::Segwit, Prefix::Hrp(hrp))
+                if *hrp == self.bip173_prefix =>
+            {
+                Ok(AddressFormat::Segwit)
+            }
+            (AddressFormat::ConfidentialBlech32, Prefix::Hrp(hrp))
+                if self.blinded_hrp() == Some(hrp) =>
+            {
+                Ok(AddressFormat::ConfidentialBlech32)
+            }
+            (_, Prefix::Base58(version)) if *version == prefixes.p2pkh => {
+                Ok(AddressFormat::P2pkh)
+            }
+            (_, Prefix::Base58(version)) if *version == prefixes.p2sh => {
+                Ok(AddressFormat::P2sh)
+            }
+            (_, Prefix::ConfidentialBase58(prefix, version))
+                if prefixes.confidential == Some(*prefix)
+                    && (*version == prefixes.p2pkh
+                        || *version == prefixes.p2sh) =>
+            {
+                Ok(AddressFormat::ConfidentialBase58)
+            }
+            _ => Err(AddressError::ChainMismatch),
+        }
+    }
+}
+
+impl Chain {
+    /// Detects chains to which the address may belong. Since multiple chains
+    /// may share the same address prefixes (for instance, bitcoin testnet and
+    /// signet use the same `tb` HRP and base58 version bytes, which are also
+    /// shared with regtest for base58 addresses), every candidate from
+    /// [`Chain::known`] accepting the address is reported.
+    pub fn from_address(
+        s: &str,
+    ) -> Result<(Vec<Chain>, AddressFormat), AddressError> {
+        let candidates = Chain::known()
+            .into_iter()
+            .map(|chain| (chain.chain_params(), chain))
+            .collect::<Vec<_>>();
+        let (format, prefix) = parse_address(
+            s,
+            candidates
+                .iter()
+                .filter_map(|(params, _)| params.blinded_hrp()),
+        )?;
+        let mut detected = None;
+        let chains = candidates
+            .into_iter()
+            .filter_map(|(params, chain)| {
+                let format = params.match_prefix(format, &prefix).ok()?;
+                detected = Some(format);
+                Some(chain)
+            })
+            .collect();
+        detected
+            .map(|format| (chains, format))
+            .ok_or(AddressError::UnknownPrefix)
+    }
+
+    /// Checks that the address belongs to the chain, returning its format
+    #[inline]
+    pub fn check_address(
+        &self,
+        s: &str,
+    ) -> Result<AddressFormat, AddressError> {
+        self.chain_params().check_address(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitcoin::bech32::ToBase32;
+
+    use super::*;
+    use crate::CHAIN_PARAMS_REGTEST;
+
+    #[test]
+    fn detect_chain() {
+        let _lock = crate::registry::test_lock();
+        let regtest = Chain::Regtest(CHAIN_PARAMS_REGTEST.genesis_hash);
+
+        assert_eq!(
+            Chain::from_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2").unwrap(),
+            (vec![Chain::Mainnet], AddressFormat::P2pkh)
+        );
+        assert_eq!(
+            Chain::from_address("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy").unwrap(),
+            (vec![Chain::Mainnet], AddressFormat::P2sh)
+        );
+        assert_eq!(
+            Chain::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+                .unwrap(),
+            (vec![Chain::Mainnet], AddressFormat::Segwit)
+        );
+        assert_eq!(
+            Chain::from_address("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4")
+                .unwrap(),
+            (vec![Chain::Mainnet], AddressFormat::Segwit)
+        );
+        assert_eq!(
+            Chain::from_address(
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"
+            )
+            .unwrap(),
+            (vec![Chain::Mainnet], AddressFormat::Segwit)
+        );
+
+        // `tb` ambiguity
+        assert_eq!(
+            Chain::from_address("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx")
+                .unwrap(),
+            (
+                vec![Chain::Testnet3, Chain::Testnet4, Chain::Signet],
+                AddressFormat::Segwit
+            )
+        );
+        assert_eq!(
+            Chain::from_address("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn").unwrap(),
+            (
+                vec![
+                    Chain::Testnet3,
+                    Chain::Testnet4,
+                    Chain::Signet,
+                    regtest.clone()
+                ],
+                AddressFormat::P2pkh
+            )
+        );
+        assert_eq!(
+            Chain::from_address("bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080")
+                .unwrap(),
+            (vec![regtest], AddressFormat::Segwit)
+        );
+    }
+
+    /// Encodes blech32 address with a given witness version, blinding key of
+    /// `0x02` bytes and 32-byte witness program
+    fn blech32_encode(hrp: &str, version: u8, constant: u64) -> String {
+        let mut program = vec![2u8; 33];
+        program.extend([0xA5u8; 32]);
+        let mut data = vec![version];
+        data.extend(program.to_base32().into_iter().map(bech32::u5::to_u8));
+        let checksum = blech32_polymod(
+            hrp,
+            &[&data[..], &[0u8; BLECH32_CHECKSUM_LEN]].concat(),
+        ) ^ constant;
+        data.extend((0..BLECH32_CHECKSUM_LEN).map(|i| {
+            ((checksum >> (5 * (BLECH32_CHECKSUM_LEN - 1 - i))) & 0x1f) as u8
+        }));
+        let charset = BECH32_CHARSET.as_bytes();
+        let data = data.into_iter().map(|v| charset[v as usize] as char);
+        format!("{}1{}", hrp, data.collect::<String>())
+    }
+
+    #[test]
+    fn liquid_addresses() {
+        let _lock = crate::registry::test_lock();
+        assert_eq!(
+            Chain::from_address("QLFdUboUPJnUzvsXKu83hUtrQ1DuxyggRg").unwrap(),
+            (vec![Chain::LiquidV1], AddressFormat::P2pkh)
+        );
+        let mut data = vec![bech32::u5::try_from_u8(0).unwrap()];
+        data.extend(vec![0u8; 20].to_base32());
+        let segwit = bech32::encode("ex", data, Variant::Bech32).unwrap();
+        assert_eq!(
+            Chain::from_address(&segwit).unwrap(),
+            (vec![Chain::LiquidV1], AddressFormat::Segwit)
+        );
+        assert_eq!(
+            Chain::from_address(
+                "lq1qqvxk052kf3qtkxmrakx50a9gc3smqad2ync54hzntjt980kfej9kkfe0\
+                 247rp5h4yzmdftsahhw64uy8pzfe7cpg4fgykm7cv"
+            )
+            .unwrap(),
+            (vec![Chain::LiquidV1], AddressFormat::ConfidentialBlech32)
+        );
+
+        // Checksum of blech32 addresses is verified
+        assert_eq!(
+            Chain::from_address(
+                "lq1qqvxk052kf3qtkxmrakx50a9gc3smqad2ync54hzntjt980kfej9kkfe0\
+                 247rp5h4yzmdftsahhw64uy8pzfe7cpg4fgykm7cw"
+            )
+            .unwrap_err(),
+            AddressError::Bech32(bech32::Error::InvalidChecksum)
+        );
+        assert_eq!(
+            Chain::from_address(&blech32_encode("lq", 1, BLECH32M_CONST))
+                .unwrap(),
+            (vec![Chain::LiquidV1], AddressFormat::ConfidentialBlech32)
+        );
+        assert_eq!(
+            Chain::LiquidTestnet
+                .check_address(&blech32_encode("tlq", 0, BLECH32_CONST))
+                .unwrap(),
+            AddressFormat::ConfidentialBlech32
+        );
+        assert_eq!(
+            Chain::from_address(&blech32_encode("lq", 1, BLECH32_CONST))
+                .unwrap_err(),
+            AddressError::WrongVariant
+        );
+
+        let blinding_key = [2u8; 33];
+        let mut data = vec![12u8, 57u8];
+        data.extend_from_slice(&blinding_key);
+        data.extend_from_slice(&[0u8; 20]);
+        let confidential = base58::check_encode_slice(&data);
+        assert_eq!(
+            Chain::LiquidV1.check_address(&confidential).unwrap(),
+            AddressFormat::ConfidentialBase58
+        );
+        assert_eq!(
+            Chain::Mainnet.check_address(&confidential).unwrap_err(),
+            AddressError::ChainMismatch
+        );
+    }
+
+    #[test]
+    fn check_address() {
+        assert_eq!(
+            Chain::Mainnet
+                .check_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2")
+                .unwrap(),
+            AddressFormat::P2pkh
+        );
+        assert_eq!(
+            Chain::Testnet3
+                .check_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2")
+                .unwrap_err(),
+            AddressError::ChainMismatch
+        );
+        assert_eq!(
+            Chain::Signet
+                .check_address("2MzQwSSnBHWHqSAqtTVQ6v47XtaisrJa1Vc")
+                .unwrap(),
+            AddressFormat::P2sh
+        );
+        assert_eq!(
+            Chain::LiquidV1
+                .check_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+                .unwrap_err(),
+            AddressError::ChainMismatch
+        );
+    }
+
+    #[test]
+    fn address_errors() {
+        let _lock = crate::registry::test_lock();
+        assert_eq!(
+            Chain::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5")
+                .unwrap_err(),
+            AddressError::Bech32(bech32::Error::InvalidChecksum)
+        );
+        assert!(matches!(
+            Chain::from_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3")
+                .unwrap_err(),
+            AddressError::Base58(base58::Error::BadChecksum(..))
+        ));
+        // BIP350 test vector: witness v1 must use bech32m
+        assert_eq!(
+            Chain::from_address(
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7k7grplx"
+            )
+            .unwrap_err(),
+            AddressError::WrongVariant
+        );
+        assert_eq!(
+            Chain::from_address(&base58::check_encode_slice(&[0x42; 21]))
+                .unwrap_err(),
+            AddressError::UnknownPrefix
+        );
+        assert_eq!(
+            Chain::from_address(&base58::check_encode_slice(&[0x00; 22]))
+                .unwrap_err(),
+            AddressError::InvalidLength(22)
+        );
+    }
+}
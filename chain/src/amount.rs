@@ -0,0 +1,455 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Asset amounts, formatted and parsed according to the asset divisibility.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+use crate::{AssetParams, Chain};
+
+/// Errors parsing and performing arithmetic operations with asset amounts
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum AmountError {
+    /// `{0}` is not a valid decimal amount
+    InvalidNumber(String),
+
+    /// unit `{0}` does not match the asset ticker, unit of accounting or
+    /// indivisible unit name
+    UnknownUnit(String),
+
+    /// amount has more fractional digits than the asset divisibility allows
+    ExcessPrecision,
+
+    /// asset divisibility {0} is not a power of ten, so its amounts can't be
+    /// represented as decimal fractions of the unit of accounting
+    NonDecimalDivisibility(u64),
+
+    /// amount value overflows 64-bit number of indivisible units
+    Overflow,
+
+    /// amounts of different assets can't be combined
+    AssetMismatch,
+
+    /// division of the amount by zero
+    DivisionByZero,
+}
+
+/// Rounding mode used when an exact amount can't be represented in
+/// indivisible units of the asset
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Display)]
+#[display(Debug)]
+pub enum Rounding {
+    /// Round towards zero
+    Down,
+
+    /// Round away from zero
+    Up,
+
+    /// Round to the nearest value, rounding halves away from zero
+    Nearest,
+}
+
+impl Rounding {
+    fn apply(self, quotient: u128, remainder: u128, divisor: u128) -> u128 {
+        match self {
+            _ if remainder == 0 => quotient,
+            Rounding::Down => quotient,
+            Rounding::Up => quotient + 1,
+            Rounding::Nearest if remainder * 2 >= divisor => quotient + 1,
+            Rounding::Nearest => quotient,
+        }
+    }
+}
+
+impl AssetParams {
+    /// Returns number of decimal digits in the fractional part of amounts
+    /// expressed in the units of accounting, or `None` if the asset
+    /// divisibility is not a power of ten
+    pub fn decimal_precision(&self) -> Option<u8> {
+        (0..=19u8).find(|precision| {
+            10u64.checked_pow(*precision as u32) == Some(self.divisibility)
+        })
+    }
+
+    fn is_accounting_unit(&self, unit: &str) -> bool {
+        unit.eq_ignore_ascii_case(&self.ticker)
+            || unit.eq_ignore_ascii_case(&self.unit_of_accounting)
+    }
+
+    fn is_indivisible_unit(&self, unit: &str) -> bool {
+        let unit = unit.to_lowercase();
+        let name = self.indivisible_unit.to_lowercase();
+        let word = name.rsplit(' ').next().unwrap_or_default();
+        let abbr = word.get(..3).unwrap_or(word);
+        [name.as_str(), word, abbr]
+            .iter()
+            .filter(|form| !form.is_empty())
+            .any(|form| unit == *form || unit == format!("{}s", form))
+    }
+}
+
+/// Amount of some asset, measured in indivisible units of the asset and tied
+/// to the asset parameters.
+///
+/// Amounts are displayed as decimal fractions of the unit of accounting
+/// followed by the asset ticker (like `0.001 BTC`); alternate formatting
+/// (`{:#}`) uses indivisible units instead (like `100000 satoshi`). Amounts of
+/// different assets are not comparable and can't be combined.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AssetAmount {
+    asset: AssetParams,
+    value: u64,
+}
+
+impl PartialOrd for AssetAmount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.asset != other.asset {
+            return None;
+        }
+        Some(self.value.cmp(&other.value))
+    }
+}
+
+impl AssetAmount {
+    /// Constructs amount from a number of indivisible units of the asset
+    #[inline]
+    pub fn with(asset: AssetParams, value: u64) -> AssetAmount {
+        AssetAmount { asset, value }
+    }
+
+    /// Constructs zero amount of the asset
+    #[inline]
+    pub fn zero(asset: AssetParams) -> AssetAmount {
+        AssetAmount::with(asset, 0)
+    }
+
+    /// Parses amount of the asset from a string, rejecting amounts which
+    /// can't be represented exactly in the indivisible units. See
+    /// [`AssetAmount::parse_rounded`] for the supported formats.
+    #[inline]
+    pub fn parse(s: &str, asset: AssetParams) -> Result<Self, AmountError> {
+        AssetAmount::parse_inner(s, asset, None)
+    }
+
+    /// Parses amount of the asset from a string, rounding amounts which can't
+    /// be represented exactly in the indivisible units.
+    ///
+    /// The string must contain a decimal number, optionally followed by a
+    /// unit. The unit may be either the asset ticker or the name of the unit
+    /// of accounting (like `BTC` or `Bitcoin`), or the name of indivisible
+    /// unit, its last word, or the abbreviation made of its three first
+    /// letters, optionally in plural form (like `satoshi`, `sats` or `sat`).
+    /// Units are case-insensitive. Numbers without unit are interpreted in
+    /// the units of accounting.
+    #[inline]
+    pub fn parse_rounded(
+        s: &str,
+        asset: AssetParams,
+        rounding: Rounding,
+    ) -> Result<Self, AmountError> {
+        AssetAmount::parse_inner(s, asset, Some(rounding))
+    }
+
+    fn parse_inner(
+        s: &str,
+        asset: AssetParams,
+        rounding: Option<Rounding>,
+    ) -> Result<Self, AmountError> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let unit = unit.trim();
+        if number.is_empty() {
+            return Err(AmountError::InvalidNumber(s.to_owned()));
+        }
+
+        let scale = if unit.is_empty() || asset.is_accounting_unit(unit) {
+            asset.decimal_precision().ok_or(
+                AmountError::NonDecimalDivisibility(asset.divisibility),
+            )?
+        } else if asset.is_indivisible_unit(unit) {
+            0
+        } else {
+            return Err(AmountError::UnknownUnit(unit.to_owned()));
+        };
+
+        let invalid = || AmountError::InvalidNumber(number.to_owned());
+        let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+        if (int.is_empty() && frac.is_empty())
+            || frac.contains('.')
+            || frac.len() > 38
+        {
+            return Err(invalid());
+        }
+        let digits = |s: &str| -> Result<u128, AmountError> {
+            if s.is_empty() {
+                return Ok(0);
+            }
+            s.parse::<u128>().map_err(|_| AmountError::Overflow)
+        };
+        let int = digits(int)?;
+        let frac_value = digits(frac)?;
+
+        // Amount is `(int + frac_value / 10^frac.len()) * 10^scale`
+        let unit_value = 10u128.pow(scale as u32);
+        let int = int.checked_mul(unit_value).ok_or(AmountError::Overflow)?;
+        let frac_len = frac.len() as u32;
+        let frac = if frac_len <= scale as u32 {
+            frac_value * 10u128.pow(scale as u32 - frac_len)
+        } else {
+            let divisor = 10u128.pow(frac_len - scale as u32);
+            let (quotient, remainder) =
+                (frac_value / divisor, frac_value % divisor);
+            match rounding {
+                None if remainder != 0 => {
+                    return Err(AmountError::ExcessPrecision)
+                }
+                None => quotient,
+                Some(rounding) => rounding.apply(quotient, remainder, divisor),
+            }
+        };
+        let value = int
+            .checked_add(frac)
+            .and_then(|value| u64::try_from(value).ok())
+            .ok_or(AmountError::Overflow)?;
+        Ok(AssetAmount::with(asset, value))
+    }
+
+    /// Returns parameters of the asset
+    #[inline]
+    pub fn asset(&self) -> &AssetParams { &self.asset }
+
+    /// Returns amount value in indivisible units of the asset
+    #[inline]
+    pub fn value(&self) -> u64 { self.value }
+
+    /// Detects whether the amount is zero
+    #[inline]
+    pub fn is_zero(&self) -> bool { self.value == 0 }
+
+    fn same_asset(&self, other: &AssetAmount) -> Result<(), AmountError> {
+        if self.asset != other.asset {
+            return Err(AmountError::AssetMismatch);
+        }
+        Ok(())
+    }
+
+    fn map(&self, value: Option<u64>) -> Result<AssetAmount, AmountError> {
+        value
+            .map(|value| AssetAmount::with(self.asset.clone(), value))
+            .ok_or(AmountError::Overflow)
+    }
+
+    /// Adds two amounts of the same asset, failing on overflow
+    pub fn checked_add(
+        &self,
+        other: &AssetAmount,
+    ) -> Result<AssetAmount, AmountError> {
+        self.same_asset(other)?;
+        self.map(self.value.checked_add(other.value))
+    }
+
+    /// Subtracts amount of the same asset, failing if the result is negative
+    pub fn checked_sub(
+        &self,
+        other: &AssetAmount,
+    ) -> Result<AssetAmount, AmountError> {
+        self.same_asset(other)?;
+        self.map(self.value.checked_sub(other.value))
+    }
+
+    /// Multiplies amount by a number, failing on overflow
+    pub fn checked_mul(&self, factor: u64) -> Result<AssetAmount, AmountError> {
+        self.map(self.value.checked_mul(factor))
+    }
+
+    /// Divides amount by a number, rounding the result
+    pub fn checked_div(
+        &self,
+        divisor: u64,
+        rounding: Rounding,
+    ) -> Result<AssetAmount, AmountError> {
+        if divisor == 0 {
+            return Err(AmountError::DivisionByZero);
+        }
+        let (value, divisor) = (self.value as u128, divisor as u128);
+        let value = rounding.apply(value / divisor, value % divisor, divisor);
+        self.map(u64::try_from(value).ok())
+    }
+}
+
+impl Display for AssetAmount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let precision = match self.asset.decimal_precision() {
+            Some(precision) if !f.alternate() => precision as usize,
+            _ => {
+                return write!(
+                    f,
+                    "{} {}",
+                    self.value, self.asset.indivisible_unit
+                )
+            }
+        };
+        let divisibility = self.asset.divisibility;
+        write!(f, "{}", self.value / divisibility)?;
+        let frac =
+            format!("{:0width$}", self.value % divisibility, width = precision);
+        let frac = frac.trim_end_matches('0');
+        if !frac.is_empty() {
+            write!(f, ".{}", frac)?;
+        }
+        write!(f, " {}", self.asset.ticker)
+    }
+}
+
+impl Chain {
+    /// Constructs amount of the native chain asset from a number of its
+    /// indivisible units
+    #[inline]
+    pub fn native_amount(&self, value: u64) -> AssetAmount {
+        AssetAmount::with(self.chain_params().native_asset, value)
+    }
+
+    /// Parses amount of the native chain asset, rejecting amounts which can't
+    /// be represented exactly in the indivisible units
+    #[inline]
+    pub fn parse_amount(&self, s: &str) -> Result<AssetAmount, AmountError> {
+        AssetAmount::parse(s, self.chain_params().native_asset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn btc(s: &str) -> Result<u64, AmountError> {
+        Chain::Mainnet.parse_amount(s).map(|amount| amount.value())
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(btc("0.001 BTC"), Ok(100_000));
+        assert_eq!(btc("0.001btc"), Ok(100_000));
+        assert_eq!(btc("1 Bitcoin"), Ok(100_000_000));
+        assert_eq!(btc("1500 sat"), Ok(1500));
+        assert_eq!(btc("1500 sats"), Ok(1500));
+        assert_eq!(btc("1 satoshi"), Ok(1));
+        assert_eq!(btc("21000000"), Ok(2_100_000_000_000_000));
+        assert_eq!(btc(".5"), Ok(50_000_000));
+        assert_eq!(btc("5."), Ok(500_000_000));
+        assert_eq!(btc("0.00000001 BTC"), Ok(1));
+        assert_eq!(
+            Chain::Testnet3
+                .parse_amount("10 test satoshis")
+                .unwrap()
+                .value(),
+            10
+        );
+        assert_eq!(Chain::LiquidV1.parse_amount("10 sat").unwrap().value(), 10);
+
+        assert_eq!(btc("0.000000001 BTC"), Err(AmountError::ExcessPrecision));
+        assert_eq!(btc("1.5 sat"), Err(AmountError::ExcessPrecision));
+        assert_eq!(btc("1 ETH"), Err(AmountError::UnknownUnit(s!("ETH"))));
+        assert_eq!(btc("."), Err(AmountError::InvalidNumber(s!("."))));
+        assert_eq!(btc("1.2.3"), Err(AmountError::InvalidNumber(s!("1.2.3"))));
+        assert_eq!(
+            btc("-1 BTC"),
+            Err(AmountError::InvalidNumber(s!("-1 BTC")))
+        );
+        assert_eq!(
+            btc("184467440737.09551616 BTC"),
+            Err(AmountError::Overflow)
+        );
+        assert_eq!(
+            btc("18446744073709551615 sat"),
+            Ok(18_446_744_073_709_551_615)
+        );
+
+        let asset = Chain::Mainnet.chain_params().native_asset;
+        let parse = |s, rounding| {
+            AssetAmount::parse_rounded(s, asset.clone(), rounding)
+                .unwrap()
+                .value()
+        };
+        assert_eq!(parse("0.000000015", Rounding::Down), 1);
+        assert_eq!(parse("0.000000015", Rounding::Up), 2);
+        assert_eq!(parse("0.000000015", Rounding::Nearest), 2);
+        assert_eq!(parse("0.0000000149", Rounding::Nearest), 1);
+        assert_eq!(parse("1.5 sat", Rounding::Nearest), 2);
+    }
+
+    #[test]
+    fn display() {
+        let amount = |value| Chain::Mainnet.native_amount(value);
+        assert_eq!(amount(100_000).to_string(), "0.001 BTC");
+        assert_eq!(amount(100_000_000).to_string(), "1 BTC");
+        assert_eq!(amount(0).to_string(), "0 BTC");
+        assert_eq!(amount(123_456_789).to_string(), "1.23456789 BTC");
+        assert_eq!(format!("{:#}", amount(1500)), "1500 satoshi");
+        for value in [0, 1, 1500, 100_000_000, 123_456_789, u64::MAX] {
+            let amount = amount(value);
+            assert_eq!(
+                Chain::Mainnet.parse_amount(&amount.to_string()),
+                Ok(amount.clone())
+            );
+            assert_eq!(
+                Chain::Mainnet.parse_amount(&format!("{:#}", amount)),
+                Ok(amount)
+            );
+        }
+
+        let mut asset = Chain::Mainnet.chain_params().native_asset;
+        asset.divisibility = 12;
+        assert_eq!(asset.decimal_precision(), None);
+        let amount = AssetAmount::with(asset.clone(), 25);
+        assert_eq!(amount.to_string(), "25 satoshi");
+        assert_eq!(
+            AssetAmount::parse("1 BTC", asset),
+            Err(AmountError::NonDecimalDivisibility(12))
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let btc = |value| Chain::Mainnet.native_amount(value);
+        let lbtc = Chain::LiquidV1.native_amount(1);
+
+        assert_eq!(btc(1).checked_add(&btc(2)), Ok(btc(3)));
+        assert_eq!(btc(3).checked_sub(&btc(2)), Ok(btc(1)));
+        assert_eq!(btc(1).checked_sub(&btc(2)), Err(AmountError::Overflow));
+        assert_eq!(
+            btc(u64::MAX).checked_add(&btc(1)),
+            Err(AmountError::Overflow)
+        );
+        assert_eq!(btc(1).checked_add(&lbtc), Err(AmountError::AssetMismatch));
+        assert_eq!(btc(1).checked_sub(&lbtc), Err(AmountError::AssetMismatch));
+        assert_eq!(btc(3).checked_mul(3), Ok(btc(9)));
+        assert_eq!(btc(u64::MAX).checked_mul(2), Err(AmountError::Overflow));
+        assert_eq!(btc(10).checked_div(4, Rounding::Down), Ok(btc(2)));
+        assert_eq!(btc(10).checked_div(4, Rounding::Up), Ok(btc(3)));
+        assert_eq!(btc(10).checked_div(4, Rounding::Nearest), Ok(btc(3)));
+        assert_eq!(btc(9).checked_div(4, Rounding::Nearest), Ok(btc(2)));
+        assert_eq!(
+            btc(1).checked_div(0, Rounding::Down),
+            Err(AmountError::DivisionByZero)
+        );
+
+        assert!(btc(1) < btc(2));
+        assert_eq!(btc(1).partial_cmp(&lbtc), None);
+        assert_ne!(btc(1), lbtc);
+    }
+}
@@ -0,0 +1,227 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Human-readable representations of [`AssetId`] following conventions of
+//! different asset systems.
+//!
+//! Plain [`Display`] and [`FromStr`] implementations of [`AssetId`] use
+//! hexadecimal string in the internal byte order. Elements-based chains
+//! display confidential asset ids in the reversed byte order (like
+//! transaction ids), so [`AssetId::display_for`] and [`AssetId::parse_for`]
+//! should be used when the asset system is known. `id1...` Bech32m strings
+//! always use the internal byte order and are unambiguous for all systems.
+//!
+//! [`FromStr`]: std::str::FromStr
+
+use std::fmt::{self, Display, Formatter};
+
+use bitcoin::hashes::hex::{self, FromHex, ToHex};
+use bitcoin::hashes::Hash;
+use lnpbp_bech32::{strategies, FromBech32Str, ToBech32String, HRP_ID};
+
+use crate::{AssetId, AssetSystem};
+
+impl lnpbp_bech32::Strategy for AssetId {
+    const HRP: &'static str = HRP_ID;
+    type Strategy = strategies::UsingStrictEncoding;
+}
+
+impl AssetSystem {
+    /// Detects whether asset ids of this system are displayed as hexadecimal
+    /// strings in the reversed byte order
+    pub fn is_display_backward(self) -> bool {
+        self == AssetSystem::ConfidentialAssets
+    }
+}
+
+/// Errors parsing [`AssetId`] string representations
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum AssetIdError {
+    /// invalid hexadecimal asset id: {0}
+    #[from]
+    Hex(hex::Error),
+
+    /// invalid `id1...` asset id: {0}
+    #[from]
+    Bech32(lnpbp_bech32::Error),
+}
+
+/// Formats [`AssetId`] according to the conventions of an asset system; see
+/// [`AssetId::display_for`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct AssetIdDisplay {
+    asset_id: AssetId,
+    system: AssetSystem,
+}
+
+impl Display for AssetIdDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return f.write_str(&self.asset_id.to_bech32_string());
+        }
+        let mut bytes = self.asset_id.into_inner();
+        if self.system.is_display_backward() {
+            bytes.reverse();
+        }
+        f.write_str(&bytes.to_hex())
+    }
+}
+
+impl AssetId {
+    /// Returns formatter displaying asset id as a hexadecimal string in the
+    /// byte order used by a given asset system or, with the alternate flag
+    /// (`{:#}`), as an `id1...` Bech32m string
+    #[inline]
+    pub fn display_for(&self, system: AssetSystem) -> AssetIdDisplay {
+        AssetIdDisplay {
+            asset_id: *self,
+            system,
+        }
+    }
+
+    /// Parses asset id from `id1...` Bech32m string or a hexadecimal string in
+    /// the byte order used by a given asset system
+    pub fn parse_for(
+        s: &str,
+        system: AssetSystem,
+    ) -> Result<AssetId, AssetIdError> {
+        if s.to_ascii_lowercase().starts_with(&format!("{}1", HRP_ID)) {
+            return Ok(AssetId::from_bech32_str(s)?);
+        }
+        let mut bytes = <[u8; 32]>::from_hex(s)?;
+        if system.is_display_backward() {
+            bytes.reverse();
+        }
+        Ok(AssetId::from_inner(bytes))
+    }
+}
+
+/// Serde serialization of [`AssetId`] as a hexadecimal string in the reversed
+/// byte order, used for Elements confidential assets. To be used with
+/// `#[serde(with = "serde_asset_id_backward")]` attribute.
+#[cfg(feature = "serde")]
+pub mod serde_asset_id_backward {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::*;
+
+    /// Serializes asset id as a reversed hexadecimal string
+    pub fn serialize<S: Serializer>(
+        asset_id: &AssetId,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer
+            .collect_str(&asset_id.display_for(AssetSystem::ConfidentialAssets))
+    }
+
+    /// Deserializes asset id from a reversed hexadecimal or `id1...` string
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<AssetId, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        AssetId::parse_for(&s, AssetSystem::ConfidentialAssets)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{Chain, POLICY_ASSET_LIQUIDV1};
+
+    const LBTC: &str =
+        "6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d";
+
+    #[test]
+    fn lbtc() {
+        let lbtc = AssetId::from_slice(POLICY_ASSET_LIQUIDV1).unwrap();
+        let elements = AssetSystem::ConfidentialAssets;
+
+        assert_eq!(lbtc.display_for(elements).to_string(), LBTC);
+        assert_eq!(AssetId::parse_for(LBTC, elements), Ok(lbtc));
+        assert_eq!(
+            AssetId::parse_for(&LBTC.to_uppercase(), elements),
+            Ok(lbtc)
+        );
+
+        // Other asset systems and plain `Display` use internal byte order
+        let forward = lbtc.to_string();
+        assert_ne!(forward, LBTC);
+        assert_eq!(
+            lbtc.display_for(AssetSystem::RgbContract).to_string(),
+            forward
+        );
+        assert_eq!(AssetId::from_str(&forward), Ok(lbtc));
+        assert_eq!(
+            AssetId::parse_for(&forward, AssetSystem::NativeBlockchain),
+            Ok(lbtc)
+        );
+        assert_eq!(
+            AssetId::parse_for(&forward, AssetSystem::RgbContract),
+            Ok(lbtc)
+        );
+
+        // Bech32 representation does not depend on the asset system
+        let id = format!("{:#}", lbtc.display_for(elements));
+        assert!(id.starts_with("id1"));
+        assert_eq!(id, lbtc.to_bech32_string());
+        assert_eq!(
+            format!("{:#}", lbtc.display_for(AssetSystem::RgbContract)),
+            id
+        );
+        for system in [elements, AssetSystem::RgbContract] {
+            assert_eq!(AssetId::parse_for(&id, system), Ok(lbtc));
+            assert_eq!(
+                AssetId::parse_for(&id.to_uppercase(), system),
+                Ok(lbtc)
+            );
+        }
+
+        assert_eq!(
+            Chain::LiquidV1.elements_params().unwrap().policy_asset,
+            lbtc
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let elements = AssetSystem::ConfidentialAssets;
+        assert!(matches!(
+            AssetId::parse_for(&LBTC[2..], elements),
+            Err(AssetIdError::Hex(_))
+        ));
+        assert!(matches!(
+            AssetId::parse_for("lbtc", elements),
+            Err(AssetIdError::Hex(_))
+        ));
+        assert!(matches!(
+            AssetId::parse_for("id1qqqqqqqq", elements),
+            Err(AssetIdError::Bech32(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let liquid = Chain::LiquidV1.elements_params().unwrap();
+        let json = serde_json::to_string(&liquid).unwrap();
+        assert!(json.contains(&format!("\"policy_asset\":\"{}\"", LBTC)));
+        assert_eq!(
+            serde_json::from_str::<crate::ElementsParams>(&json).unwrap(),
+            liquid
+        );
+    }
+}
@@ -0,0 +1,496 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Registry of non-native assets (Liquid confidential assets and RGB
+//! contracts) known to an application.
+
+use std::collections::BTreeMap;
+
+use crate::{AssetId, AssetParams, AssetSystem, Chain, NativeAsset};
+
+/// Errors registering and looking up assets in [`AssetRegistry`]
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum AssetRegistryError {
+    /// asset {0} is a native asset of the chain and can't be registered
+    NativeAsset(AssetId),
+
+    /// {0} assets can't be defined on {1} chain
+    AssetSystemMismatch(AssetSystem, Chain),
+
+    /// no asset with ticker `{0}` is known
+    UnknownTicker(String),
+
+    /// ticker `{0}` is used by multiple assets: {1:?}
+    AmbiguousTicker(String, Vec<AssetId>),
+
+    /// invalid Liquid asset registry data: {0}
+    #[cfg(feature = "asset-registry")]
+    InvalidRegistryData(String),
+}
+
+/// Registry of assets defined on different chains, storing [`AssetParams`]
+/// by chain and [`AssetId`].
+///
+/// Native chain assets are not stored in the registry, but are always known
+/// to it: they can be looked up by their identifier and ticker on the
+/// corresponding chain, and native asset tickers take precedence over
+/// tickers of the registered assets.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct AssetRegistry {
+    assets: BTreeMap<Chain, BTreeMap<AssetId, AssetParams>>,
+}
+
+/// Checks whether an asset is known to a given [`AssetRegistry`], which
+/// happens if it is either the native asset of the chain or was registered.
+pub trait KnownAsset {
+    /// Detects whether the asset is known on a given chain
+    fn is_known(&self, chain: &Chain, registry: &AssetRegistry) -> bool;
+}
+
+impl KnownAsset for AssetId {
+    fn is_known(&self, chain: &Chain, registry: &AssetRegistry) -> bool {
+        registry.get(chain, self).is_some()
+    }
+}
+
+impl KnownAsset for Option<AssetId> {
+    fn is_known(&self, chain: &Chain, registry: &AssetRegistry) -> bool {
+        match self {
+            Some(asset_id) => asset_id.is_known(chain, registry),
+            None => true,
+        }
+    }
+}
+
+impl AssetRegistry {
+    /// Constructs empty registry
+    #[inline]
+    pub fn new() -> AssetRegistry { AssetRegistry::default() }
+
+    fn is_native(chain: &Chain, asset_id: &AssetId) -> bool {
+        asset_id.is_native(chain)
+            || chain.chain_params().elements.map(|e| e.policy_asset)
+                == Some(*asset_id)
+    }
+
+    /// Registers asset on a given chain, returning parameters of the asset
+    /// which was previously registered under the same identifier.
+    ///
+    /// Only assets of [`AssetSystem::ConfidentialAssets`] (on Elements-based
+    /// chains) and [`AssetSystem::RgbContract`] systems may be registered.
+    pub fn register(
+        &mut self,
+        chain: Chain,
+        params: AssetParams,
+    ) -> Result<Option<AssetParams>, AssetRegistryError> {
+        if AssetRegistry::is_native(&chain, &params.asset_id) {
+            return Err(AssetRegistryError::NativeAsset(params.asset_id));
+        }
+        match params.asset_system {
+            AssetSystem::RgbContract => {}
+            AssetSystem::ConfidentialAssets
+                if chain.chain_params().elements.is_some() => {}
+            system => {
+                return Err(AssetRegistryError::AssetSystemMismatch(
+                    system, chain,
+                ))
+            }
+        }
+        Ok(self
+            .assets
+            .entry(chain)
+            .or_default()
+            .insert(params.asset_id, params))
+    }
+
+    /// Removes asset from the registry, returning its parameters if it was
+    /// registered
+    pub fn unregister(
+        &mut self,
+        chain: &Chain,
+        asset_id: &AssetId,
+    ) -> Option<AssetParams> {
+        let assets = self.assets.get_mut(chain)?;
+        let params = assets.remove(asset_id);
+        if assets.is_empty() {
+            self.assets.remove(chain);
+        }
+        params
+    }
+
+    /// Returns parameters of an asset known on a given chain, including the
+    /// native chain asset (which on Elements-based chains may be also
+    /// referenced by the policy asset id)
+    pub fn get(
+        &self,
+        chain: &Chain,
+        asset_id: &AssetId,
+    ) -> Option<AssetParams> {
+        if AssetRegistry::is_native(chain, asset_id) {
+            return Some(chain.chain_params().native_asset);
+        }
+        self.assets.get(chain)?.get(asset_id).cloned()
+    }
+
+    /// Lists all assets known on a given chain having a specific ticker,
+    /// with the native chain asset going first. Tickers are
+    /// case-sensitive.
+    pub fn find_by_ticker(
+        &self,
+        chain: &Chain,
+        ticker: &str,
+    ) -> Vec<AssetParams> {
+        let native = chain.chain_params().native_asset;
+        std::iter::once(native)
+            .filter(|native| native.ticker == ticker)
+            .chain(
+                self.assets(chain)
+                    .filter(|params| params.ticker == ticker)
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    /// Returns parameters of an asset known on a given chain by its ticker.
+    ///
+    /// Native chain asset ticker always resolves to the native asset. If the
+    /// ticker is used by more than a single registered asset, fails with
+    /// [`AssetRegistryError::AmbiguousTicker`], listing all the assets, so
+    /// the caller can ask user to choose the asset by its identifier.
+    pub fn by_ticker(
+        &self,
+        chain: &Chain,
+        ticker: &str,
+    ) -> Result<AssetParams, AssetRegistryError> {
+        let mut found = self.find_by_ticker(chain, ticker);
+        match found.len() {
+            0 => Err(AssetRegistryError::UnknownTicker(ticker.to_owned())),
+            1 => Ok(found.remove(0)),
+            _ if found[0].asset_system == AssetSystem::NativeBlockchain => {
+                Ok(found.remove(0))
+            }
+            _ => Err(AssetRegistryError::AmbiguousTicker(
+                ticker.to_owned(),
+                found.into_iter().map(|params| params.asset_id).collect(),
+            )),
+        }
+    }
+
+    /// Iterates over all assets registered on a given chain, ordered by their
+    /// identifiers. Native chain asset is not included.
+    pub fn assets<'registry>(
+        &'registry self,
+        chain: &Chain,
+    ) -> impl Iterator<Item = &'registry AssetParams> {
+        self.assets
+            .get(chain)
+            .into_iter()
+            .flat_map(BTreeMap::values)
+    }
+
+    /// Returns number of assets registered on all chains
+    pub fn len(&self) -> usize { self.assets.values().map(BTreeMap::len).sum() }
+
+    /// Detects whether no assets were registered
+    pub fn is_empty(&self) -> bool { self.assets.is_empty() }
+}
+
+#[cfg(feature = "asset-registry")]
+mod liquid {
+    use serde_json::Value;
+
+    use super::*;
+
+    fn invalid(details: impl ToString) -> AssetRegistryError {
+        AssetRegistryError::InvalidRegistryData(details.to_string())
+    }
+
+    fn parse_entry(
+        id: Option<&str>,
+        entry: &Value,
+    ) -> Result<AssetParams, AssetRegistryError> {
+        // Fields of the full entries are duplicated in the contract, which is
+        // the only place having them in some of the older dumps
+        let field = |name: &str| {
+            entry.get(name).or_else(|| entry.get("contract")?.get(name))
+        };
+        let id = field("asset_id")
+            .and_then(Value::as_str)
+            .or(id)
+            .ok_or_else(|| invalid("entry without asset id"))?;
+        let asset_id = AssetId::parse_for(id, AssetSystem::ConfidentialAssets)
+            .map_err(|_| invalid(format!("invalid asset id `{}`", id)))?;
+
+        let name = field("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid(format!("asset {} has no name", id)))?;
+        let ticker = field("ticker").and_then(Value::as_str).unwrap_or("");
+        let precision = field("precision")
+            .map(Value::as_u64)
+            .unwrap_or(Some(0))
+            .filter(|precision| *precision <= 8)
+            .ok_or_else(|| {
+                invalid(format!("asset {} has invalid precision", id))
+            })?;
+
+        Ok(AssetParams {
+            ticker: ticker.to_owned(),
+            unit_of_accounting: name.to_owned(),
+            indivisible_unit: format!(
+                "{} base unit",
+                if ticker.is_empty() { name } else { ticker }
+            ),
+            divisibility: 10u64.pow(precision as u32),
+            asset_id,
+            asset_system: AssetSystem::ConfidentialAssets,
+        })
+    }
+
+    impl AssetRegistry {
+        /// Imports assets from a local dump of the Liquid asset registry in
+        /// JSON format, registering them on a given Elements-based chain and
+        /// returning number of the imported assets.
+        ///
+        /// The dump may be either an object mapping asset ids to the registry
+        /// entries (the format of the registry `index.json`) or an array of
+        /// the entries (the format of Esplora registry API responses). Each
+        /// entry must contain asset `name` and may contain `ticker` and
+        /// `precision`. Entries for the native chain asset are skipped.
+        /// Assets are imported only if all entries are valid.
+        pub fn import_liquid_registry(
+            &mut self,
+            chain: &Chain,
+            json: &str,
+        ) -> Result<usize, AssetRegistryError> {
+            let dump: Value = serde_json::from_str(json).map_err(invalid)?;
+            let assets = match &dump {
+                Value::Object(map) => map
+                    .iter()
+                    .map(|(id, entry)| parse_entry(Some(id), entry))
+                    .collect::<Result<Vec<_>, _>>()?,
+                Value::Array(list) => list
+                    .iter()
+                    .map(|entry| parse_entry(None, entry))
+                    .collect::<Result<Vec<_>, _>>()?,
+                _ => return Err(invalid("dump must be an object or an array")),
+            };
+
+            let mut registry = self.clone();
+            let mut count = 0usize;
+            for params in assets {
+                if AssetRegistry::is_native(chain, &params.asset_id) {
+                    continue;
+                }
+                registry.register(chain.clone(), params)?;
+                count += 1;
+            }
+            *self = registry;
+            Ok(count)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitcoin::hashes::Hash;
+
+    use super::*;
+
+    fn asset(ticker: &str, system: AssetSystem) -> AssetParams {
+        AssetParams {
+            ticker: ticker.to_owned(),
+            unit_of_accounting: format!("{} token", ticker),
+            indivisible_unit: format!("{} base unit", ticker),
+            divisibility: 100,
+            asset_id: AssetId::hash(
+                format!("{}{:?}", ticker, system).as_bytes(),
+            ),
+            asset_system: system,
+        }
+    }
+
+    #[test]
+    fn register() {
+        let mut registry = AssetRegistry::new();
+        let usdt = asset("USDt", AssetSystem::ConfidentialAssets);
+        let rgb = asset("RGB", AssetSystem::RgbContract);
+        let id = usdt.asset_id;
+
+        assert_eq!(registry.register(Chain::LiquidV1, usdt.clone()), Ok(None));
+        assert_eq!(registry.register(Chain::Mainnet, rgb.clone()), Ok(None));
+        assert_eq!(
+            registry.register(Chain::LiquidV1, usdt.clone()),
+            Ok(Some(usdt.clone()))
+        );
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.get(&Chain::LiquidV1, &id), Some(usdt.clone()));
+        assert_eq!(registry.get(&Chain::LiquidTestnet, &id), None);
+        assert_eq!(registry.assets(&Chain::Mainnet).collect::<Vec<_>>(), vec![
+            &rgb
+        ]);
+
+        assert_eq!(
+            registry.register(Chain::Mainnet, usdt.clone()),
+            Err(AssetRegistryError::AssetSystemMismatch(
+                AssetSystem::ConfidentialAssets,
+                Chain::Mainnet
+            ))
+        );
+        let native = Chain::LiquidV1.chain_params().native_asset;
+        assert_eq!(
+            registry.register(Chain::LiquidV1, native.clone()),
+            Err(AssetRegistryError::NativeAsset(native.asset_id))
+        );
+
+        assert!(id.is_known(&Chain::LiquidV1, &registry));
+        assert!(!id.is_known(&Chain::LiquidTestnet, &registry));
+        assert!(native.asset_id.is_known(&Chain::LiquidV1, &registry));
+        let policy_asset = Chain::LiquidV1
+            .chain_params()
+            .elements
+            .unwrap()
+            .policy_asset;
+        assert_eq!(registry.get(&Chain::LiquidV1, &policy_asset), Some(native));
+        assert!(None.is_known(&Chain::LiquidV1, &registry));
+
+        assert_eq!(registry.unregister(&Chain::LiquidV1, &id), Some(usdt));
+        assert_eq!(registry.unregister(&Chain::LiquidV1, &id), None);
+        assert!(!Some(id).is_known(&Chain::LiquidV1, &registry));
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn ticker_collisions() {
+        let mut registry = AssetRegistry::new();
+        let usdt = asset("USDt", AssetSystem::ConfidentialAssets);
+        let fake_usdt = AssetParams {
+            asset_id: AssetId::hash(b"fake"),
+            ..usdt.clone()
+        };
+        let fake_btc = asset("LBTC", AssetSystem::ConfidentialAssets);
+        registry.register(Chain::LiquidV1, usdt.clone()).unwrap();
+        registry
+            .register(Chain::LiquidV1, fake_btc.clone())
+            .unwrap();
+
+        assert_eq!(registry.by_ticker(&Chain::LiquidV1, "USDt"), Ok(usdt));
+        assert_eq!(
+            registry.by_ticker(&Chain::LiquidV1, "usdt"),
+            Err(AssetRegistryError::UnknownTicker(s!("usdt")))
+        );
+
+        registry
+            .register(Chain::LiquidV1, fake_usdt.clone())
+            .unwrap();
+        assert_eq!(registry.find_by_ticker(&Chain::LiquidV1, "USDt").len(), 2);
+        assert!(matches!(
+            registry.by_ticker(&Chain::LiquidV1, "USDt"),
+            Err(AssetRegistryError::AmbiguousTicker(_, ids)) if ids.len() == 2
+        ));
+
+        let native = Chain::LiquidV1.chain_params().native_asset;
+        assert_eq!(
+            registry.find_by_ticker(&Chain::LiquidV1, &native.ticker),
+            vec![native.clone(), fake_btc]
+        );
+        assert_eq!(
+            registry.by_ticker(&Chain::LiquidV1, &native.ticker),
+            Ok(native)
+        );
+        assert_eq!(
+            registry.by_ticker(&Chain::Mainnet, "BTC"),
+            Ok(Chain::Mainnet.chain_params().native_asset)
+        );
+    }
+
+    #[cfg(feature = "asset-registry")]
+    #[test]
+    fn import_liquid_registry() {
+        let index = r#"{
+            "ce091c998b83c78bb71a632313ba3760f1763d9cfcffae02258ffa9865a37bd2": {
+                "asset_id": "ce091c998b83c78bb71a632313ba3760f1763d9cfcffae02258ffa9865a37bd2",
+                "contract": {
+                    "entity": { "domain": "tether.to" },
+                    "name": "Tether USD",
+                    "precision": 8,
+                    "ticker": "USDt",
+                    "version": 0
+                },
+                "name": "Tether USD",
+                "ticker": "USDt",
+                "precision": 8
+            },
+            "6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d": {
+                "name": "Liquid Bitcoin",
+                "ticker": "L-BTC",
+                "precision": 8
+            },
+            "0e99c1a6da379d1f4151fb9df90449d40d0608f6cb33a5bcbfc8c265f42bab0a": {
+                "contract": { "name": "Liquid CAD", "ticker": "LCAD" }
+            }
+        }"#;
+
+        let mut registry = AssetRegistry::new();
+        assert_eq!(
+            registry.import_liquid_registry(&Chain::LiquidV1, index),
+            Ok(2)
+        );
+        let usdt = registry.by_ticker(&Chain::LiquidV1, "USDt").unwrap();
+        assert_eq!(usdt.unit_of_accounting, "Tether USD");
+        assert_eq!(usdt.divisibility, 100_000_000);
+        assert_eq!(usdt.asset_system, AssetSystem::ConfidentialAssets);
+        assert_eq!(
+            usdt.asset_id
+                .display_for(AssetSystem::ConfidentialAssets)
+                .to_string(),
+            "ce091c998b83c78bb71a632313ba3760f1763d9cfcffae02258ffa9865a37bd2"
+        );
+        let lcad = registry.by_ticker(&Chain::LiquidV1, "LCAD").unwrap();
+        assert_eq!(lcad.divisibility, 1);
+
+        let list = r#"[{
+            "asset_id": "18729918ab4bca843656f08d4dd877bed6641fbd596a0a963abbf199cfeb3cec",
+            "name": "PEG in ETH",
+            "ticker": "PETH",
+            "precision": 8
+        }]"#;
+        assert_eq!(
+            registry.import_liquid_registry(&Chain::LiquidV1, list),
+            Ok(1)
+        );
+        assert_eq!(registry.len(), 3);
+
+        // Nothing is imported if a single entry is invalid
+        for invalid in [
+            r#"[{ "asset_id": "00", "name": "Short" }]"#,
+            r#"[{ "asset_id": "18729918ab4bca843656f08d4dd877bed6641fbd596a0a963abbf199cfeb3ced" }]"#,
+            r#"[{ "asset_id": "18729918ab4bca843656f08d4dd877bed6641fbd596a0a963abbf199cfeb3ced", "name": "X", "precision": 9 }]"#,
+            r#""string""#,
+            "{",
+        ] {
+            assert!(matches!(
+                registry.import_liquid_registry(&Chain::LiquidV1, invalid),
+                Err(AssetRegistryError::InvalidRegistryData(_))
+            ));
+        }
+        assert_eq!(
+            registry.import_liquid_registry(&Chain::Mainnet, list),
+            Err(AssetRegistryError::AssetSystemMismatch(
+                AssetSystem::ConfidentialAssets,
+                Chain::Mainnet
+            ))
+        );
+        assert_eq!(registry.len(), 3);
+    }
+}
@@ -0,0 +1,357 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! BIP32 extended key version bytes ("magic numbers") according to SLIP-132.
+
+use bitcoin::util::base58;
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::{Bip32MagicNumber, Chain};
+
+/// Application of BIP32 extended keys, defining which script types are
+/// derived from them and, thus, version bytes (magic numbers) used for their
+/// serialization according to SLIP-132
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Display, Hash)]
+#[derive(StrictEncode, StrictDecode)]
+#[display(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "kebab-case")
+)]
+#[repr(u8)]
+pub enum KeyApplication {
+    /// P2PKH and other non-segwit single-key outputs (BIP44), as well as
+    /// taproot outputs (BIP86): `xpub`/`tpub` keys
+    Hashed = 0,
+
+    /// P2WPKH nested in P2SH (BIP49): `ypub`/`upub` keys
+    NestedWpkh = 1,
+
+    /// Native P2WPKH (BIP84): `zpub`/`vpub` keys
+    SegWit = 2,
+
+    /// P2WSH multisig nested in P2SH: `Ypub`/`Upub` keys
+    NestedMultisig = 3,
+
+    /// Native P2WSH multisig: `Zpub`/`Vpub` keys
+    SegWitMultisig = 4,
+}
+
+impl KeyApplication {
+    /// Enumerates all known key applications
+    pub fn all() -> &'static [KeyApplication] {
+        &[
+            KeyApplication::Hashed,
+            KeyApplication::NestedWpkh,
+            KeyApplication::SegWit,
+            KeyApplication::NestedMultisig,
+            KeyApplication::SegWitMultisig,
+        ]
+    }
+}
+
+/// Pair of BIP32 magic numbers (version bytes) used for the serialization of
+/// extended public and private keys
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+#[derive(StrictEncode, StrictDecode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+pub struct Bip32Version {
+    /// Magic number for extended public keys
+    pub public: Bip32MagicNumber,
+
+    /// Magic number for extended private keys
+    pub private: Bip32MagicNumber,
+}
+
+impl Bip32Version {
+    /// Constructs version bytes from a pair of public and private key magic
+    /// numbers
+    pub const fn with(
+        public: Bip32MagicNumber,
+        private: Bip32MagicNumber,
+    ) -> Bip32Version {
+        Bip32Version { public, private }
+    }
+}
+
+/// Full set of BIP32 magic numbers (SLIP-132 version bytes) used by a chain
+/// for extended keys of each [`KeyApplication`]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+#[derive(StrictEncode, StrictDecode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+pub struct Bip32Versions {
+    /// Version bytes for [`KeyApplication::Hashed`] keys
+    pub hashed: Bip32Version,
+
+    /// Version bytes for [`KeyApplication::NestedWpkh`] keys
+    pub nested_wpkh: Bip32Version,
+
+    /// Version bytes for [`KeyApplication::SegWit`] keys
+    pub segwit: Bip32Version,
+
+    /// Version bytes for [`KeyApplication::NestedMultisig`] keys
+    pub nested_multisig: Bip32Version,
+
+    /// Version bytes for [`KeyApplication::SegWitMultisig`] keys
+    pub segwit_multisig: Bip32Version,
+}
+
+impl Bip32Versions {
+    /// SLIP-132 version bytes used by bitcoin mainnet: `xpub`, `ypub`,
+    /// `zpub`, `Ypub`, `Zpub` and their private key counterparts
+    pub const MAINNET: Bip32Versions = Bip32Versions {
+        hashed: Bip32Version::with(0x0488B21E, 0x0488ADE4),
+        nested_wpkh: Bip32Version::with(0x049D7CB2, 0x049D7878),
+        segwit: Bip32Version::with(0x04B24746, 0x04B2430C),
+        nested_multisig: Bip32Version::with(0x0295B43F, 0x0295B005),
+        segwit_multisig: Bip32Version::with(0x02AA7ED3, 0x02AA7A99),
+    };
+
+    /// SLIP-132 version bytes used by bitcoin testnets, signets and regtest:
+    /// `tpub`, `upub`, `vpub`, `Upub`, `Vpub` and their private key
+    /// counterparts
+    pub const TESTNET: Bip32Versions = Bip32Versions {
+        hashed: Bip32Version::with(0x043587CF, 0x04358394),
+        nested_wpkh: Bip32Version::with(0x044A5262, 0x044A4E28),
+        segwit: Bip32Version::with(0x045F1CF6, 0x045F18BC),
+        nested_multisig: Bip32Version::with(0x024289EF, 0x024285B5),
+        segwit_multisig: Bip32Version::with(0x02575483, 0x02575048),
+    };
+
+    /// Returns version bytes for a given key application
+    pub fn version(&self, application: KeyApplication) -> Bip32Version {
+        match application {
+            KeyApplication::Hashed => self.hashed,
+            KeyApplication::NestedWpkh => self.nested_wpkh,
+            KeyApplication::SegWit => self.segwit,
+            KeyApplication::NestedMultisig => self.nested_multisig,
+            KeyApplication::SegWitMultisig => self.segwit_multisig,
+        }
+    }
+
+    /// Detects type of the extended key from its magic number, returning
+    /// [`Option::None`] if the magic number is not used by the chain
+    pub fn key_type(&self, magic: Bip32MagicNumber) -> Option<XkeyType> {
+        KeyApplication::all().iter().find_map(|application| {
+            let version = self.version(*application);
+            match magic {
+                m if m == version.public => Some(XkeyType {
+                    application: *application,
+                    is_private: false,
+                }),
+                m if m == version.private => Some(XkeyType {
+                    application: *application,
+                    is_private: true,
+                }),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Type of BIP32 extended key detected from its version bytes
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct XkeyType {
+    /// Application of the extended key
+    pub application: KeyApplication,
+
+    /// Whether the key is an extended private key
+    pub is_private: bool,
+}
+
+/// Errors detecting chain and type of BIP32 extended keys
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum XkeyError {
+    /// extended key has invalid Base58 encoding: {0}
+    #[from]
+    Base58(base58::Error),
+
+    /// extended key must be 78 bytes long, while {0} bytes were provided
+    WrongLength(usize),
+
+    /// extended key version bytes {0:#010x} are not used by any known chain
+    UnknownVersion(Bip32MagicNumber),
+}
+
+impl Chain {
+    /// Returns BIP32 magic numbers (SLIP-132 version bytes) used by the chain
+    pub fn bip32_versions(&self) -> Bip32Versions {
+        self.chain_params().bip32_versions
+    }
+
+    /// Returns BIP32 magic numbers used by the chain for a given extended
+    /// key application
+    pub fn bip32_version(&self, application: KeyApplication) -> Bip32Version {
+        self.bip32_versions().version(application)
+    }
+
+    /// Detects chains and type of an extended key from its magic number
+    /// (version bytes). Since the same magic numbers are shared by multiple
+    /// chains (like bitcoin mainnet and Liquid, or all bitcoin testnets), all
+    /// of the [`Chain::known`] chains using the magic number are returned.
+    pub fn detect_xkey_version(
+        magic: Bip32MagicNumber,
+    ) -> Result<(Vec<Chain>, XkeyType), XkeyError> {
+        let mut key_type = None;
+        let chains = Chain::known()
+            .into_iter()
+            .filter(|chain| match chain.bip32_versions().key_type(magic) {
+                None => false,
+                detected => {
+                    key_type = detected;
+                    true
+                }
+            })
+            .collect();
+        key_type
+            .map(|key_type| (chains, key_type))
+            .ok_or(XkeyError::UnknownVersion(magic))
+    }
+
+    /// Detects chains and type of an extended key from its Base58Check
+    /// string representation. See [`Chain::detect_xkey_version`] for the
+    /// details.
+    pub fn detect_xkey(s: &str) -> Result<(Vec<Chain>, XkeyType), XkeyError> {
+        let data = base58::from_check(s)?;
+        if data.len() != 78 {
+            return Err(XkeyError::WrongLength(data.len()));
+        }
+        let mut magic = [0u8; 4];
+        magic.copy_from_slice(&data[..4]);
+        Chain::detect_xkey_version(Bip32MagicNumber::from_be_bytes(magic))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitcoin::util::bip32::{ExtendedPrivKey, ExtendedPubKey};
+    use strict_encoding_test::{
+        test_encoding_enum, test_encoding_enum_by_values,
+        test_encoding_enum_u8_exhaustive,
+    };
+
+    use super::*;
+    use crate::CHAIN_PARAMS_REGTEST;
+
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhe\
+                        PY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const ZPUB: &str = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNf\
+                        E3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+    const TPRV: &str = "tprv8ZgxMBicQKsPd7Uf69XL1XwhmjHopUGep8GuEiJDZmbQz6o58LninorQA\
+                        fcKZWARbtRtfnLcJ5MQ2AtHcQJCCRUcMRvmDUjyEmNUWwx8UbK";
+
+    #[test]
+    fn key_application_encoding() {
+        test_encoding_enum_u8_exhaustive!(KeyApplication;
+            KeyApplication::Hashed => 0u8,
+            KeyApplication::NestedWpkh => 1u8,
+            KeyApplication::SegWit => 2u8,
+            KeyApplication::NestedMultisig => 3u8,
+            KeyApplication::SegWitMultisig => 4u8
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn chain_versions() {
+        assert_eq!(Chain::Mainnet.bip32_versions(), Bip32Versions::MAINNET);
+        assert_eq!(Chain::LiquidV1.bip32_versions(), Bip32Versions::MAINNET);
+        assert_eq!(Chain::Testnet3.bip32_versions(), Bip32Versions::TESTNET);
+        assert_eq!(Chain::Signet.bip32_versions(), Bip32Versions::TESTNET);
+        assert_eq!(
+            Chain::Regtest(CHAIN_PARAMS_REGTEST.genesis_hash).bip32_versions(),
+            Bip32Versions::TESTNET
+        );
+        assert_eq!(
+            Chain::Mainnet.bip32_version(KeyApplication::Hashed),
+            Bip32Version::with(0x0488B21E, 0x0488ADE4)
+        );
+        assert_eq!(
+            Chain::Testnet3.bip32_version(KeyApplication::SegWit).public,
+            0x045F1CF6
+        );
+
+        // Compatibility with rust-bitcoin constants
+        let secp = bitcoin::secp256k1::Secp256k1::new();
+        for (chain, network) in [
+            (Chain::Mainnet, bitcoin::Network::Bitcoin),
+            (Chain::Testnet3, bitcoin::Network::Testnet),
+        ] {
+            let xpriv =
+                ExtendedPrivKey::new_master(network, &[0u8; 32]).unwrap();
+            let xpub = ExtendedPubKey::from_priv(&secp, &xpriv);
+            let version = chain.bip32_version(KeyApplication::Hashed);
+            assert_eq!(xpriv.encode()[..4], version.private.to_be_bytes());
+            assert_eq!(xpub.encode()[..4], version.public.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn detect_xkey() {
+        let _lock = crate::registry::test_lock();
+        let (chains, key_type) = Chain::detect_xkey(XPUB).unwrap();
+        assert_eq!(chains, vec![Chain::Mainnet, Chain::LiquidV1]);
+        assert_eq!(key_type, XkeyType {
+            application: KeyApplication::Hashed,
+            is_private: false
+        });
+
+        let (chains, key_type) = Chain::detect_xkey(ZPUB).unwrap();
+        assert_eq!(chains, vec![Chain::Mainnet, Chain::LiquidV1]);
+        assert_eq!(key_type, XkeyType {
+            application: KeyApplication::SegWit,
+            is_private: false
+        });
+
+        let (chains, key_type) = Chain::detect_xkey(TPRV).unwrap();
+        assert_eq!(chains, vec![
+            Chain::Testnet3,
+            Chain::Testnet4,
+            Chain::Signet,
+            Chain::LiquidTestnet,
+            Chain::Regtest(CHAIN_PARAMS_REGTEST.genesis_hash)
+        ]);
+        assert_eq!(key_type, XkeyType {
+            application: KeyApplication::Hashed,
+            is_private: true
+        });
+    }
+
+    #[test]
+    fn detect_xkey_errors() {
+        let _lock = crate::registry::test_lock();
+        assert_eq!(
+            Chain::detect_xkey_version(0x01020304).unwrap_err(),
+            XkeyError::UnknownVersion(0x01020304)
+        );
+        assert!(matches!(
+            Chain::detect_xkey(&XPUB[1..]).unwrap_err(),
+            XkeyError::Base58(_)
+        ));
+        assert_eq!(
+            Chain::detect_xkey(&base58::check_encode_slice(&[0x04; 40]))
+                .unwrap_err(),
+            XkeyError::WrongLength(40)
+        );
+    }
+}
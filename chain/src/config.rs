@@ -0,0 +1,594 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Loading and saving chain parameters from TOML, YAML and JSON configuration
+//! files, and user directory containing definitions of custom chains.
+
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+use crate::{ChainFormat, ChainParams};
+
+/// Environment variable overriding location of the user chain directory
+pub const CHAIN_DIR_ENV: &str = "LNPBP_CHAIN_DIR";
+
+/// Format of chain configuration files
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Display, Hash)]
+#[display(Debug)]
+pub enum ConfigFormat {
+    /// TOML format (`.toml` files)
+    Toml,
+
+    /// YAML format (`.yaml` and `.yml` files)
+    Yaml,
+
+    /// JSON format (`.json` files)
+    Json,
+}
+
+impl ConfigFormat {
+    /// Enumerates all supported configuration formats
+    pub fn all() -> &'static [ConfigFormat] {
+        &[ConfigFormat::Toml, ConfigFormat::Yaml, ConfigFormat::Json]
+    }
+
+    /// Returns default file extension for the format
+    pub fn extension(self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Json => "json",
+        }
+    }
+
+    /// Detects configuration format from a file extension
+    pub fn from_extension(ext: &str) -> Option<ConfigFormat> {
+        match ext.to_ascii_lowercase().as_str() {
+            "toml" => Some(ConfigFormat::Toml),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "json" => Some(ConfigFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Detects configuration format from a file path extension
+    pub fn from_path(path: impl AsRef<Path>) -> Option<ConfigFormat> {
+        path.as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(ConfigFormat::from_extension)
+    }
+}
+
+/// Errors loading, saving and validating chain configuration files
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum ConfigError {
+    /// I/O error accessing chain configuration: {0}
+    #[from]
+    Io(io::Error),
+
+    /// file `{0}` has unknown configuration format; supported file
+    /// extensions are `toml`, `yaml`, `yml` and `json`
+    UnknownFormat(String),
+
+    /// invalid chain configuration at `{path}`: {details}
+    Parse {
+        /// Path to the field which can't be parsed
+        path: String,
+        /// Details of the parse error
+        details: String,
+    },
+
+    /// unable to serialize chain parameters: {0}
+    Serialize(String),
+
+    /// invalid chain parameters: field `{field}` {reason}
+    InvalidField {
+        /// Name of the invalid field
+        field: &'static str,
+        /// Reason why the field value is invalid
+        reason: &'static str,
+    },
+
+    /// chain `{0}` is not defined in the chain directory
+    UnknownChain(String),
+}
+
+impl ConfigError {
+    fn parse<E: std::fmt::Display>(
+        err: serde_path_to_error::Error<E>,
+    ) -> ConfigError {
+        ConfigError::Parse {
+            path: err.path().to_string(),
+            details: err.into_inner().to_string(),
+        }
+    }
+}
+
+fn is_valid_hrp(hrp: &str) -> bool {
+    (1..=83).contains(&hrp.len())
+        && hrp
+            .bytes()
+            .all(|b| (33..=126).contains(&b) && !b.is_ascii_uppercase())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.bytes().all(|b| {
+            b.is_ascii_lowercase()
+                || b.is_ascii_digit()
+                || b == b'-'
+                || b == b'_'
+        })
+}
+
+const NAME_REASON: &str =
+    "must be a non-empty lowercase alphanumeric identifier";
+
+fn check(
+    valid: bool,
+    field: &'static str,
+    reason: &'static str,
+) -> Result<(), ConfigError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError::InvalidField { field, reason })
+    }
+}
+
+impl ChainParams {
+    /// Validates consistency of chain parameters, reporting the first invalid
+    /// field
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check(is_valid_name(&self.name), "name", NAME_REASON)?;
+        check(
+            !self.bip70_name.is_empty(),
+            "bip70_name",
+            "must not be empty",
+        )?;
+        check(
+            is_valid_hrp(&self.bip173_prefix),
+            "bip173_prefix",
+            "must be a valid lowercase bech32 human-readable part",
+        )?;
+        check(
+            self.address_prefixes.p2pkh != self.address_prefixes.p2sh,
+            "address_prefixes.p2sh",
+            "must differ from P2PKH version byte",
+        )?;
+        check(self.p2p_port != 0, "p2p_port", "must not be zero")?;
+        check(
+            self.rpc_port != 0 && self.rpc_port != self.p2p_port,
+            "rpc_port",
+            "must be non-zero and differ from P2P port",
+        )?;
+        check(
+            self.dust_limit == self.relay_policy.p2pkh_dust_limit(),
+            "dust_limit",
+            "must match P2PKH dust threshold of the relay policy",
+        )?;
+        check(
+            !self.native_asset.ticker.is_empty(),
+            "native_asset.ticker",
+            "must not be empty",
+        )?;
+        check(
+            self.native_asset.divisibility != 0,
+            "native_asset.divisibility",
+            "must not be zero",
+        )?;
+        check(
+            self.economics.target_spacing != 0,
+            "economics.target_spacing",
+            "must not be zero",
+        )?;
+        check(
+            !self.is_pow || self.economics.pow_limit != 0,
+            "economics.pow_limit",
+            "must be provided for PoW chains",
+        )?;
+        match (self.format, &self.elements) {
+            (ChainFormat::Elements, Some(elements)) => check(
+                is_valid_hrp(&elements.blinded_hrp),
+                "elements.blinded_hrp",
+                "must be a valid lowercase bech32 human-readable part",
+            ),
+            (ChainFormat::Elements, None) => Err(ConfigError::InvalidField {
+                field: "elements",
+                reason: "must be provided for chains using elements format",
+            }),
+            (ChainFormat::Bitcoin, Some(_)) => Err(ConfigError::InvalidField {
+                field: "elements",
+                reason: "must be absent for chains using bitcoin format",
+            }),
+            (ChainFormat::Bitcoin, None) => Ok(()),
+        }
+    }
+
+    /// Parses and validates chain parameters from a configuration string.
+    /// Keys of the document are snake case field names of [`ChainParams`]
+    /// and its nested structures; unknown keys are rejected, so misspelled
+    /// optional sections are not silently replaced with their defaults.
+    pub fn from_config_str(
+        s: &str,
+        format: ConfigFormat,
+    ) -> Result<ChainParams, ConfigError> {
+        let params: ChainParams = match format {
+            // TOML deserializer can't parse enum map keys (like deployment
+            // names), so the document is converted into JSON value first
+            ConfigFormat::Toml => {
+                let value = toml::from_str::<toml::Value>(s)
+                    .map_err(|err| ConfigError::Parse {
+                        path: s!("."),
+                        details: err.to_string(),
+                    })
+                    .and_then(|value| {
+                        serde_json::to_value(value).map_err(|err| {
+                            ConfigError::Parse {
+                                path: s!("."),
+                                details: err.to_string(),
+                            }
+                        })
+                    })?;
+                serde_path_to_error::deserialize(value)
+                    .map_err(ConfigError::parse)?
+            }
+            ConfigFormat::Yaml => serde_path_to_error::deserialize(
+                serde_yaml::Deserializer::from_str(s),
+            )
+            .map_err(ConfigError::parse)?,
+            ConfigFormat::Json => serde_path_to_error::deserialize(
+                &mut serde_json::Deserializer::from_str(s),
+            )
+            .map_err(ConfigError::parse)?,
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Serializes chain parameters into a configuration string
+    pub fn to_config_string(
+        &self,
+        format: ConfigFormat,
+    ) -> Result<String, ConfigError> {
+        let err = |err: &dyn std::fmt::Display| {
+            ConfigError::Serialize(err.to_string())
+        };
+        match format {
+            // Serializing through `toml::Value` puts plain values before
+            // tables, as required by TOML
+            ConfigFormat::Toml => toml::Value::try_from(self)
+                .and_then(|value| toml::to_string_pretty(&value))
+                .map_err(|e| err(&e)),
+            ConfigFormat::Yaml => {
+                serde_yaml::to_string(self).map_err(|e| err(&e))
+            }
+            ConfigFormat::Json => {
+                serde_json::to_string_pretty(self).map_err(|e| err(&e))
+            }
+        }
+    }
+
+    /// Loads and validates chain parameters from a configuration file, using
+    /// its extension to detect the format
+    pub fn load(path: impl AsRef<Path>) -> Result<ChainParams, ConfigError> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path).ok_or_else(|| {
+            ConfigError::UnknownFormat(path.display().to_string())
+        })?;
+        ChainParams::from_config_str(&fs::read_to_string(path)?, format)
+    }
+
+    /// Saves chain parameters into a configuration file, using its extension
+    /// to detect the format
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path).ok_or_else(|| {
+            ConfigError::UnknownFormat(path.display().to_string())
+        })?;
+        fs::write(path, self.to_config_string(format)?)?;
+        Ok(())
+    }
+}
+
+/// Directory with configuration files defining custom chains, one chain per
+/// file named after the chain ([`ChainParams::name`])
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ChainDir {
+    path: PathBuf,
+}
+
+impl ChainDir {
+    /// Constructs chain directory located at a given path
+    pub fn with(path: impl Into<PathBuf>) -> ChainDir {
+        ChainDir { path: path.into() }
+    }
+
+    /// Returns user chain directory. Its location can be overridden with
+    /// [`CHAIN_DIR_ENV`] environment variable; otherwise it is `lnpbp/chains`
+    /// inside the user configuration directory. Returns `None` if the user
+    /// configuration directory can't be detected.
+    pub fn user() -> Option<ChainDir> {
+        if let Some(dir) = env::var_os(CHAIN_DIR_ENV) {
+            return Some(ChainDir::with(dir));
+        }
+        #[cfg(windows)]
+        let config_dir = env::var_os("APPDATA").map(PathBuf::from);
+        #[cfg(not(windows))]
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".config"))
+            });
+        config_dir.map(|dir| ChainDir::with(dir.join("lnpbp").join("chains")))
+    }
+
+    /// Returns path to the directory
+    #[inline]
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Loads chain with a given name from the directory. The name must be a
+    /// valid chain name (see [`ChainParams::validate`]), so it can't refer to
+    /// files outside of the directory.
+    pub fn load(&self, name: &str) -> Result<ChainParams, ConfigError> {
+        check(is_valid_name(name), "name", NAME_REASON)?;
+        let path = ["toml", "yaml", "yml", "json"]
+            .iter()
+            .map(|ext| self.path.join(format!("{}.{}", name, ext)))
+            .find(|path| path.is_file())
+            .ok_or_else(|| ConfigError::UnknownChain(name.to_owned()))?;
+        ChainDir::load_file(path, name)
+    }
+
+    /// Loads chain from a directory file, which must be named after the chain
+    fn load_file(
+        path: impl AsRef<Path>,
+        name: &str,
+    ) -> Result<ChainParams, ConfigError> {
+        let params = ChainParams::load(path)?;
+        check(
+            params.name == name,
+            "name",
+            "must match name of the chain file in the chain directory",
+        )?;
+        Ok(params)
+    }
+
+    /// Loads all chains defined in the directory, ordered by file names.
+    /// Files with unknown extensions are ignored; non-existing directory
+    /// contains no chains. Files must be named after the chains they define.
+    pub fn load_all(&self) -> Result<Vec<ChainParams>, ConfigError> {
+        if !self.path.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut paths = fs::read_dir(&self.path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.sort();
+        paths
+            .into_iter()
+            .filter(|path| {
+                path.is_file() && ConfigFormat::from_path(path).is_some()
+            })
+            .map(|path| {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                ChainDir::load_file(path, &name)
+            })
+            .collect()
+    }
+
+    /// Saves chain into the directory in a given format, creating the
+    /// directory if needed. Returns path to the saved file.
+    pub fn save(
+        &self,
+        params: &ChainParams,
+        format: ConfigFormat,
+    ) -> Result<PathBuf, ConfigError> {
+        params.validate()?;
+        fs::create_dir_all(&self.path)?;
+        let path =
+            self.path
+                .join(format!("{}.{}", params.name, format.extension()));
+        params.save(&path)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Chain, CHAIN_PARAMS_REGTEST};
+
+    fn temp_dir(name: &str) -> ChainDir {
+        let dir = env::temp_dir().join(format!(
+            "lnpbp-chain-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        ChainDir::with(dir)
+    }
+
+    #[test]
+    fn roundtrip() {
+        for chain in Chain::all_standard() {
+            let params = chain.chain_params();
+            for format in ConfigFormat::all() {
+                let s = params.to_config_string(*format).unwrap();
+                let decoded =
+                    ChainParams::from_config_str(&s, *format).unwrap();
+                assert_eq!(
+                    strict_encoding::strict_serialize(&decoded).unwrap(),
+                    strict_encoding::strict_serialize(&params).unwrap(),
+                    "{} in {}",
+                    chain,
+                    format
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut params = CHAIN_PARAMS_REGTEST.clone();
+        params.name = s!("private");
+        let json = params.to_config_string(ConfigFormat::Json).unwrap();
+
+        let broken = json.replace("\"p2p_port\": 28333", "\"p2p_port\": \"x\"");
+        match ChainParams::from_config_str(&broken, ConfigFormat::Json) {
+            Err(ConfigError::Parse { path, .. }) => {
+                assert_eq!(path, "p2p_port")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let broken = json.replace("\"p2p_port\": 28333", "\"p2p_port\": 0");
+        match ChainParams::from_config_str(&broken, ConfigFormat::Json) {
+            Err(ConfigError::InvalidField { field, .. }) => {
+                assert_eq!(field, "p2p_port")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        // Misspelled optional sections are not replaced with the defaults
+        let yaml = params.to_config_string(ConfigFormat::Yaml).unwrap();
+        let broken = yaml.replace("relay_policy:", "relay-policy:");
+        match ChainParams::from_config_str(&broken, ConfigFormat::Yaml) {
+            Err(ConfigError::Parse { path, details }) => {
+                assert_eq!(path, "relay-policy");
+                assert!(details.contains("unknown field `relay-policy`"))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        let broken = yaml.replace("dust_relay_fee:", "dust_relay_fees:");
+        match ChainParams::from_config_str(&broken, ConfigFormat::Yaml) {
+            Err(ConfigError::Parse { path, details }) => {
+                assert_eq!(path, "relay_policy.dust_relay_fees");
+                assert!(details.contains("unknown field `dust_relay_fees`"))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let toml = params.to_config_string(ConfigFormat::Toml).unwrap();
+        let broken = toml.replace("ticker = 'tBTC'", "ticker = 1");
+        match ChainParams::from_config_str(&broken, ConfigFormat::Toml) {
+            Err(ConfigError::Parse { path, .. }) => {
+                assert_eq!(path, "native_asset.ticker")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validation() {
+        let mut params = Chain::LiquidV1.chain_params();
+        params.validate().unwrap();
+        params.elements = None;
+        match params.validate() {
+            Err(ConfigError::InvalidField { field, .. }) => {
+                assert_eq!(field, "elements")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let mut params = Chain::Mainnet.chain_params();
+        params.bip173_prefix = s!("BC");
+        match params.validate() {
+            Err(ConfigError::InvalidField { field, .. }) => {
+                assert_eq!(field, "bip173_prefix")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let mut params = Chain::Mainnet.chain_params();
+        params.relay_policy.dust_relay_fee = 1000;
+        match params.validate() {
+            Err(ConfigError::InvalidField { field, .. }) => {
+                assert_eq!(field, "dust_limit")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        params.dust_limit = 182;
+        params.validate().unwrap();
+    }
+
+    #[test]
+    fn chain_dir() {
+        let dir = temp_dir("dir");
+        assert!(dir.load_all().unwrap().is_empty());
+
+        let mut private = CHAIN_PARAMS_REGTEST.clone();
+        private.name = s!("private");
+        let mut other = Chain::Signet.chain_params();
+        other.name = s!("team-signet");
+
+        let path = dir.save(&private, ConfigFormat::Toml).unwrap();
+        assert_eq!(path, dir.path().join("private.toml"));
+        dir.save(&other, ConfigFormat::Yaml).unwrap();
+        fs::write(dir.path().join("README.md"), "not a chain").unwrap();
+
+        assert_eq!(dir.load("private").unwrap().name, "private");
+        assert_eq!(dir.load("team-signet").unwrap().name, "team-signet");
+        assert!(matches!(
+            dir.load("unknown"),
+            Err(ConfigError::UnknownChain(name)) if name == "unknown"
+        ));
+        // Names must not escape the directory, even when pointing to an
+        // existing chain file
+        let escaping = format!(
+            "../{}/private",
+            dir.path().file_name().unwrap().to_string_lossy()
+        );
+        for name in ["../../x", &escaping, "Private", ""] {
+            assert!(matches!(
+                dir.load(name),
+                Err(ConfigError::InvalidField { field: "name", .. })
+            ));
+        }
+        let names = dir
+            .load_all()
+            .unwrap()
+            .into_iter()
+            .map(|params| params.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![s!("private"), s!("team-signet")]);
+
+        assert!(matches!(
+            private.save(dir.path().join("private.ini")),
+            Err(ConfigError::UnknownFormat(_))
+        ));
+
+        // Renamed chain files are rejected
+        fs::rename(
+            dir.path().join("private.toml"),
+            dir.path().join("renamed.toml"),
+        )
+        .unwrap();
+        assert!(matches!(
+            dir.load("renamed"),
+            Err(ConfigError::InvalidField { field: "name", .. })
+        ));
+        assert!(matches!(
+            dir.load_all(),
+            Err(ConfigError::InvalidField { field: "name", .. })
+        ));
+
+        fs::remove_dir_all(dir.path()).unwrap();
+    }
+}
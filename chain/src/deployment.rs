@@ -0,0 +1,268 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Soft-fork deployments and protocol activation heights.
+
+use std::collections::BTreeMap;
+
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::{Chain, ChainParams};
+
+/// Activation heights of deployments on a given chain. Deployments missing
+/// from the map are considered not active on the chain; deployments active
+/// at an unknown height are mapped to [`Deployment::UNKNOWN_HEIGHT`].
+pub type Deployments = BTreeMap<Deployment, u32>;
+
+/// Consensus soft-forks and protocols deployed on top of a chain, which
+/// activation height is tracked by [`ChainParams::deployments`]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Display, Hash)]
+#[derive(StrictEncode, StrictDecode)]
+#[display(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "kebab-case")
+)]
+#[repr(u8)]
+pub enum Deployment {
+    /// BIP34: block height in coinbase
+    Bip34 = 0,
+
+    /// BIP65: `OP_CHECKLOCKTIMEVERIFY`
+    Bip65 = 1,
+
+    /// BIP66: strict DER signatures
+    Bip66 = 2,
+
+    /// BIP68, BIP112 and BIP113: relative lock-time and
+    /// `OP_CHECKSEQUENCEVERIFY`
+    Csv = 3,
+
+    /// BIP141, BIP143 and BIP147: segregated witness
+    Segwit = 4,
+
+    /// BIP340, BIP341 and BIP342: schnorr signatures and taproot
+    Taproot = 5,
+
+    /// Lightning network started using the chain
+    Lightning = 6,
+
+    /// RGB started using the chain
+    Rgb = 7,
+}
+
+impl Deployment {
+    /// Activation height of deployments which are active on the chain, but
+    /// for which the first block they are active at is not known
+    pub const UNKNOWN_HEIGHT: u32 = u32::MAX;
+
+    /// Enumerates all known deployments
+    pub fn all() -> &'static [Deployment] {
+        &[
+            Deployment::Bip34,
+            Deployment::Bip65,
+            Deployment::Bip66,
+            Deployment::Csv,
+            Deployment::Segwit,
+            Deployment::Taproot,
+            Deployment::Lightning,
+            Deployment::Rgb,
+        ]
+    }
+
+    /// Detects whether the deployment is a consensus soft-fork (and not a
+    /// protocol operating on top of the chain)
+    pub fn is_soft_fork(self) -> bool {
+        !matches!(self, Deployment::Lightning | Deployment::Rgb)
+    }
+}
+
+impl ChainParams {
+    /// Returns height of the first block at which the deployment is active,
+    /// or `None` if the deployment is not active on the chain or its
+    /// activation height is not known
+    #[inline]
+    pub fn activation_height(&self, deployment: Deployment) -> Option<u32> {
+        self.deployments
+            .get(&deployment)
+            .copied()
+            .filter(|height| *height != Deployment::UNKNOWN_HEIGHT)
+    }
+
+    /// Checks whether the deployment is active at a given block height.
+    /// Returns `None` if the deployment is active on the chain, but its
+    /// activation height is not known.
+    #[inline]
+    pub fn is_active(
+        &self,
+        deployment: Deployment,
+        height: u32,
+    ) -> Option<bool> {
+        match self.deployments.get(&deployment) {
+            None => Some(false),
+            Some(&Deployment::UNKNOWN_HEIGHT) => None,
+            Some(activation) => Some(height >= *activation),
+        }
+    }
+
+    /// Returns height of the first block at which Lightning network started
+    /// using the chain
+    #[deprecated(
+        since = "0.10.0",
+        note = "use `activation_height(Deployment::Lightning)`"
+    )]
+    #[inline]
+    pub fn ln_height(&self) -> Option<u32> {
+        self.activation_height(Deployment::Lightning)
+    }
+
+    /// Returns height of the first block at which RGB started using the chain
+    #[deprecated(
+        since = "0.10.0",
+        note = "use `activation_height(Deployment::Rgb)`"
+    )]
+    #[inline]
+    pub fn rgb_height(&self) -> Option<u32> {
+        self.activation_height(Deployment::Rgb)
+    }
+}
+
+impl Chain {
+    /// Returns height of the first block at which the deployment is active,
+    /// or `None` if the deployment is not active on the chain or its
+    /// activation height is not known
+    #[inline]
+    pub fn activation_height(&self, deployment: Deployment) -> Option<u32> {
+        self.chain_params().activation_height(deployment)
+    }
+
+    /// Checks whether the deployment is active at a given block height.
+    /// Returns `None` if the deployment is active on the chain, but its
+    /// activation height is not known.
+    #[inline]
+    pub fn is_active(
+        &self,
+        deployment: Deployment,
+        height: u32,
+    ) -> Option<bool> {
+        self.chain_params().is_active(deployment, height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use strict_encoding_test::{
+        test_encoding_enum, test_encoding_enum_by_values,
+        test_encoding_enum_u8_exhaustive,
+    };
+
+    use super::*;
+
+    #[test]
+    fn deployment_encoding() {
+        test_encoding_enum_u8_exhaustive!(Deployment;
+            Deployment::Bip34 => 0u8,
+            Deployment::Bip65 => 1u8,
+            Deployment::Bip66 => 2u8,
+            Deployment::Csv => 3u8,
+            Deployment::Segwit => 4u8,
+            Deployment::Taproot => 5u8,
+            Deployment::Lightning => 6u8,
+            Deployment::Rgb => 7u8
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn activation() {
+        assert_eq!(
+            Chain::Mainnet.is_active(Deployment::Taproot, 709_631),
+            Some(false)
+        );
+        assert_eq!(
+            Chain::Mainnet.is_active(Deployment::Taproot, 709_632),
+            Some(true)
+        );
+        assert_eq!(
+            Chain::Mainnet.is_active(Deployment::Segwit, 481_823),
+            Some(false)
+        );
+        assert_eq!(
+            Chain::Mainnet.is_active(Deployment::Segwit, 481_824),
+            Some(true)
+        );
+        assert_eq!(
+            Chain::Mainnet.activation_height(Deployment::Bip34),
+            Some(227_931)
+        );
+        assert_eq!(
+            Chain::Testnet3.activation_height(Deployment::Csv),
+            Some(770_112)
+        );
+        assert_eq!(Chain::Signet.is_active(Deployment::Taproot, 0), Some(true));
+
+        // Taproot is active on Liquid, but at an unknown height
+        for chain in [Chain::LiquidV1, Chain::LiquidTestnet] {
+            assert_eq!(chain.activation_height(Deployment::Taproot), None);
+            assert_eq!(chain.is_active(Deployment::Taproot, 0), None);
+            assert_eq!(chain.is_active(Deployment::Taproot, u32::MAX), None);
+            assert_eq!(chain.is_active(Deployment::Segwit, 0), Some(true));
+        }
+
+        let mut params = Chain::Mainnet.chain_params();
+        params.deployments.remove(&Deployment::Taproot);
+        assert_eq!(
+            params.is_active(Deployment::Taproot, u32::MAX),
+            Some(false)
+        );
+
+        for chain in Chain::all_standard() {
+            let params = chain.chain_params();
+            for deployment in Deployment::all() {
+                match params.activation_height(*deployment) {
+                    Some(height) => assert_eq!(
+                        chain.is_active(*deployment, height),
+                        Some(true)
+                    ),
+                    None if params.deployments.contains_key(deployment) => {
+                        assert_eq!(chain.is_active(*deployment, 0), None)
+                    }
+                    None => assert_eq!(
+                        chain.is_active(*deployment, u32::MAX),
+                        Some(false)
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn legacy_heights() {
+        let params = Chain::Mainnet.chain_params();
+        assert_eq!(params.ln_height(), Some(504_500));
+        assert_eq!(params.rgb_height(), Some(650_000));
+        assert_eq!(
+            Chain::Testnet3.chain_params().ln_height(),
+            Chain::Testnet3.activation_height(Deployment::Lightning)
+        );
+    }
+
+    #[test]
+    fn soft_forks() {
+        assert!(Deployment::Taproot.is_soft_fork());
+        assert!(!Deployment::Lightning.is_soft_fork());
+        assert!(!Deployment::Rgb.is_soft_fork());
+    }
+}
@@ -0,0 +1,273 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Consensus economics of a chain: subsidy schedule and block timing.
+
+use bitcoin::util::uint::Uint256;
+use bitcoin::BlockHeader;
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::Chain;
+
+/// Maximal number of halvings after which block subsidy becomes zero
+const MAX_HALVINGS: u32 = 64;
+
+/// Consensus parameters defining subsidy schedule and block timing of a chain
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+#[derive(StrictEncode, StrictDecode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+pub struct ChainEconomics {
+    /// Timestamp of the genesis block, used as an anchor for block height
+    /// estimations
+    pub genesis_time: u32,
+
+    /// Subsidy of the blocks before the first halving, in indivisible units
+    /// of the native asset; 0 if blocks are not subsidised
+    pub initial_subsidy: u64,
+
+    /// Number of blocks between subsidy halvings; 0 if subsidy is never
+    /// halved
+    pub halving_interval: u32,
+
+    /// Target time between blocks, in seconds
+    pub target_spacing: u32,
+
+    /// Number of blocks between difficulty adjustments; 0 if the chain does
+    /// not use PoW
+    pub difficulty_adjustment_interval: u32,
+
+    /// Proof of work limit in compact ("nBits") form; 0 if the chain does not
+    /// use PoW
+    pub pow_limit: u32,
+}
+
+impl ChainEconomics {
+    /// Economics of bitcoin mainnet
+    pub const MAINNET: ChainEconomics = ChainEconomics {
+        genesis_time: 1231006505,
+        initial_subsidy: 50 * 100_000_000,
+        halving_interval: 210_000,
+        target_spacing: 10 * 60,
+        difficulty_adjustment_interval: 2016,
+        pow_limit: 0x1d00ffff,
+    };
+
+    /// Economics of bitcoin testnet3
+    pub const TESTNET: ChainEconomics = ChainEconomics {
+        genesis_time: 1296688602,
+        ..ChainEconomics::MAINNET
+    };
+
+    /// Economics of bitcoin testnet4
+    pub const TESTNET4: ChainEconomics = ChainEconomics {
+        genesis_time: 1714777860,
+        ..ChainEconomics::MAINNET
+    };
+
+    /// Economics of bitcoin regtest
+    pub const REGTEST: ChainEconomics = ChainEconomics {
+        genesis_time: 1296688602,
+        halving_interval: 150,
+        pow_limit: 0x207fffff,
+        ..ChainEconomics::MAINNET
+    };
+
+    /// Economics of bitcoin signet
+    pub const SIGNET: ChainEconomics = ChainEconomics {
+        genesis_time: 1598918400,
+        pow_limit: 0x1e0377ae,
+        ..ChainEconomics::MAINNET
+    };
+
+    /// Economics of Liquid sidechains, which have no block subsidy and no PoW
+    pub const LIQUIDV1: ChainEconomics = ChainEconomics {
+        genesis_time: 1296688602,
+        initial_subsidy: 0,
+        halving_interval: 0,
+        target_spacing: 60,
+        difficulty_adjustment_interval: 0,
+        pow_limit: 0,
+    };
+
+    /// Returns subsidy of the block at a given height
+    pub fn block_subsidy(&self, height: u32) -> u64 {
+        if self.halving_interval == 0 {
+            return self.initial_subsidy;
+        }
+        let halvings = height / self.halving_interval;
+        if halvings >= MAX_HALVINGS {
+            return 0;
+        }
+        self.initial_subsidy >> halvings
+    }
+
+    /// Returns total amount of the native asset issued as block subsidy by
+    /// the blocks up to (and including) a given height, counting genesis
+    /// block subsidy. The value saturates at `u64::MAX` for custom chains with
+    /// the supply exceeding it.
+    pub fn issued_supply(&self, height: u32) -> u64 {
+        let blocks = height as u64 + 1;
+        if self.halving_interval == 0 {
+            return self.initial_subsidy.saturating_mul(blocks);
+        }
+        let interval = self.halving_interval as u64;
+        (0..MAX_HALVINGS)
+            .map(|halving| {
+                let start = halving as u64 * interval;
+                let count = blocks.saturating_sub(start).min(interval);
+                (self.initial_subsidy >> halving).saturating_mul(count)
+            })
+            .fold(0u64, u64::saturating_add)
+    }
+
+    /// Estimates height of the block mined at a given timestamp, assuming
+    /// blocks were produced at the target rate since the genesis
+    pub fn estimated_height(&self, timestamp: u32) -> u32 {
+        if self.target_spacing == 0 {
+            return 0;
+        }
+        timestamp.saturating_sub(self.genesis_time) / self.target_spacing
+    }
+
+    /// Returns proof of work limit as a target value, or `None` if the chain
+    /// does not use PoW
+    pub fn pow_limit_target(&self) -> Option<Uint256> {
+        if self.pow_limit == 0 {
+            return None;
+        }
+        Some(BlockHeader::u256_from_compact_target(self.pow_limit))
+    }
+}
+
+impl Chain {
+    /// Returns subsidy of the block at a given height
+    #[inline]
+    pub fn block_subsidy(&self, height: u32) -> u64 {
+        self.chain_params().economics.block_subsidy(height)
+    }
+
+    /// Returns total amount of the native asset issued as block subsidy by
+    /// the blocks up to (and including) a given height
+    #[inline]
+    pub fn issued_supply(&self, height: u32) -> u64 {
+        self.chain_params().economics.issued_supply(height)
+    }
+
+    /// Estimates height of the block mined at a given timestamp
+    #[inline]
+    pub fn estimated_height(&self, timestamp: u32) -> u32 {
+        self.chain_params().economics.estimated_height(timestamp)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitcoin::blockdata::constants::genesis_block;
+    use bitcoin::Network;
+
+    use super::*;
+
+    #[test]
+    fn subsidy() {
+        assert_eq!(Chain::Mainnet.block_subsidy(0), 5_000_000_000);
+        assert_eq!(Chain::Mainnet.block_subsidy(209_999), 5_000_000_000);
+        assert_eq!(Chain::Mainnet.block_subsidy(210_000), 2_500_000_000);
+        assert_eq!(Chain::Mainnet.block_subsidy(840_000), 312_500_000);
+        assert_eq!(Chain::Mainnet.block_subsidy(6_930_000), 0);
+        assert_eq!(Chain::Mainnet.block_subsidy(u32::MAX), 0);
+        assert_eq!(Chain::LiquidV1.block_subsidy(1_000_000), 0);
+
+        let regtest = ChainEconomics::REGTEST;
+        assert_eq!(regtest.block_subsidy(149), 5_000_000_000);
+        assert_eq!(regtest.block_subsidy(150), 2_500_000_000);
+    }
+
+    #[test]
+    fn supply() {
+        assert_eq!(Chain::Mainnet.issued_supply(0), 5_000_000_000);
+        assert_eq!(
+            Chain::Mainnet.issued_supply(209_999),
+            1_050_000_000_000_000
+        );
+        assert_eq!(
+            Chain::Mainnet.issued_supply(210_000),
+            1_050_002_500_000_000
+        );
+        assert_eq!(
+            Chain::Mainnet.issued_supply(u32::MAX),
+            2_099_999_997_690_000
+        );
+        assert_eq!(Chain::LiquidV1.issued_supply(u32::MAX), 0);
+
+        let economics = ChainEconomics::MAINNET;
+        let brute = (0..=420_000u32)
+            .map(|height| economics.block_subsidy(height))
+            .sum::<u64>();
+        assert_eq!(economics.issued_supply(420_000), brute);
+
+        let extreme = ChainEconomics {
+            halving_interval: 4_000_000_000,
+            ..ChainEconomics::MAINNET
+        };
+        assert_eq!(extreme.issued_supply(u32::MAX), u64::MAX);
+        assert_eq!(extreme.issued_supply(999), 5_000_000_000_000);
+        let extreme = ChainEconomics {
+            initial_subsidy: u64::MAX,
+            halving_interval: 1,
+            ..ChainEconomics::MAINNET
+        };
+        assert_eq!(extreme.issued_supply(0), u64::MAX);
+        assert_eq!(extreme.issued_supply(u32::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn timing() {
+        let genesis = ChainEconomics::MAINNET.genesis_time;
+        assert_eq!(genesis, genesis_block(Network::Bitcoin).header.time);
+        assert_eq!(
+            ChainEconomics::TESTNET.genesis_time,
+            genesis_block(Network::Testnet).header.time,
+        );
+        assert_eq!(
+            ChainEconomics::SIGNET.genesis_time,
+            genesis_block(Network::Signet).header.time,
+        );
+        assert_eq!(Chain::Mainnet.estimated_height(0), 0);
+        assert_eq!(Chain::Mainnet.estimated_height(genesis), 0);
+        assert_eq!(Chain::Mainnet.estimated_height(genesis + 6_000), 10);
+        assert_eq!(Chain::LiquidV1.estimated_height(1296688602 + 600), 10);
+    }
+
+    #[test]
+    fn pow_limit() {
+        assert_eq!(
+            ChainEconomics::MAINNET.pow_limit_target(),
+            Some(BlockHeader::u256_from_compact_target(
+                genesis_block(Network::Bitcoin).header.bits
+            ))
+        );
+        assert_eq!(
+            ChainEconomics::REGTEST.pow_limit,
+            genesis_block(Network::Regtest).header.bits
+        );
+        assert_eq!(
+            ChainEconomics::SIGNET.pow_limit,
+            genesis_block(Network::Signet).header.bits
+        );
+        assert_eq!(ChainEconomics::LIQUIDV1.pow_limit_target(), None);
+    }
+}
@@ -0,0 +1,120 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Parameters specific for Elements-based sidechains.
+
+use bitcoin::Script;
+use strict_encoding::{StrictDecode, StrictEncode};
+
+#[cfg(feature = "serde")]
+use crate::serde_asset_id_backward;
+use crate::{AssetId, Chain, ChainFormat, ChainParams};
+
+/// Policy asset (L-BTC) of Liquid V1 sidechain, in internal byte order
+pub const POLICY_ASSET_LIQUIDV1: &[u8] = &[
+    0x6d, 0x52, 0x1c, 0x38, 0xec, 0x1e, 0xa1, 0x57, 0x34, 0xae, 0x22, 0xb7,
+    0xc4, 0x60, 0x64, 0x41, 0x28, 0x29, 0xc0, 0xd0, 0x57, 0x9f, 0x0a, 0x71,
+    0x3d, 0x1c, 0x04, 0xed, 0xe9, 0x79, 0x02, 0x6f,
+];
+
+/// Policy asset (tL-BTC) of Liquid testnet sidechain, in internal byte order
+pub const POLICY_ASSET_LIQUID_TESTNET: &[u8] = &[
+    0x49, 0x9a, 0x81, 0x85, 0x45, 0xf6, 0xba, 0xe3, 0x9f, 0xc0, 0x3b, 0x63,
+    0x7f, 0x2a, 0x4e, 0x1e, 0x64, 0xe5, 0x90, 0xca, 0xc1, 0xbc, 0x3a, 0x6f,
+    0x6d, 0x71, 0xaa, 0x44, 0x43, 0x65, 0x4c, 0x14,
+];
+
+/// Parameters of chains using [`ChainFormat::Elements`] format
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[derive(StrictEncode, StrictDecode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+pub struct ElementsParams {
+    /// Number of confirmations on the parent chain required for a peg-in
+    /// transaction to be claimed on the sidechain
+    pub pegin_confirmation_depth: u32,
+
+    /// Script of the federation controlling the peg, or `None` if it is not
+    /// tracked (for instance, because the chain uses dynamic federations and
+    /// the current script must be taken from the block headers)
+    pub fedpeg_script: Option<Script>,
+
+    /// Policy asset of the chain, used to pay transaction fees. Serialized in
+    /// the reversed byte order, like Elements displays asset ids.
+    #[cfg_attr(feature = "serde", serde(with = "serde_asset_id_backward"))]
+    pub policy_asset: AssetId,
+
+    /// HRP used by blinded (confidential) blech32 addresses
+    pub blinded_hrp: String,
+}
+
+impl ChainParams {
+    /// Returns HRP used by blinded (confidential) blech32 addresses on chains
+    /// using [`ChainFormat::Elements`] format
+    pub fn blinded_hrp(&self) -> Option<&str> {
+        match self.format {
+            ChainFormat::Elements => {
+                self.elements.as_ref().map(|e| e.blinded_hrp.as_str())
+            }
+            ChainFormat::Bitcoin => None,
+        }
+    }
+}
+
+impl Chain {
+    /// Returns Elements-specific parameters for chains using
+    /// [`ChainFormat::Elements`] format, or `None` for other chains
+    pub fn elements_params(&self) -> Option<ElementsParams> {
+        let params = self.chain_params();
+        match params.format {
+            ChainFormat::Elements => params.elements,
+            ChainFormat::Bitcoin => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AssetSystem;
+
+    #[test]
+    fn liquid_params() {
+        let liquid = Chain::LiquidV1.elements_params().unwrap();
+        assert_eq!(liquid.pegin_confirmation_depth, 102);
+        assert_eq!(liquid.blinded_hrp, "lq");
+        assert_eq!(
+            liquid
+                .policy_asset
+                .display_for(AssetSystem::ConfidentialAssets)
+                .to_string(),
+            "6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d"
+        );
+
+        let testnet = Chain::LiquidTestnet.elements_params().unwrap();
+        assert_eq!(testnet.blinded_hrp, "tlq");
+        assert_eq!(
+            testnet
+                .policy_asset
+                .display_for(AssetSystem::ConfidentialAssets)
+                .to_string(),
+            "144c654344aa716d6f3abcc1ca90e5641e4e2a7f633bc09fe3baf64585819a49"
+        );
+
+        assert_eq!(Chain::Mainnet.elements_params(), None);
+        assert_eq!(Chain::Testnet4.elements_params(), None);
+    }
+}
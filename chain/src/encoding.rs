@@ -0,0 +1,358 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Versioned strict encoding of [`ChainParams`].
+//!
+//! Encoded parameters start with [`CHAIN_PARAMS_MAGIC`] and
+//! [`CHAIN_PARAMS_VERSION`] bytes, followed by the fixed set of core fields
+//! and a list of TLV extension records, each having `u16` type and
+//! length-prefixed value. New fields are added as extension records, so the
+//! data remain readable by the older software. Following BOLT convention,
+//! records with odd types are optional and are skipped by the decoders which
+//! do not know them, while unknown even types make decoding fail.
+//!
+//! Data starting with anything other than the magic bytes are decoded using
+//! legacy unversioned layout, which is a plain list of the fields existing
+//! before the encoding was versioned. Lightning and RGB activation heights of
+//! the legacy layout are converted into [`ChainParams::deployments`]; other
+//! fields missing from it are taken from the built-in chain with the same
+//! genesis hash or, for unknown chains, take their default values.
+
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+
+use bitcoin::BlockHash;
+use strict_encoding::{
+    strict_deserialize, strict_encode_list, strict_serialize, StrictDecode,
+    StrictEncode,
+};
+
+use crate::{
+    AddressPrefixes, AssetParams, Bip32Versions, Chain, ChainEconomics,
+    ChainFormat, ChainParams, ChainSeeds, Deployment, Deployments,
+    P2pNetworkId, RelayPolicy, ServicePorts,
+};
+
+/// Magic bytes starting versioned encoding of [`ChainParams`]
+pub const CHAIN_PARAMS_MAGIC: [u8; 4] = [0xFF, b'C', b'H', b'N'];
+
+/// Current version of [`ChainParams`] encoding
+pub const CHAIN_PARAMS_VERSION: u8 = 1;
+
+/// Extension record containing [`ChainParams::elements`]
+const TLV_ELEMENTS: u16 = 0;
+/// Extension record containing [`ChainParams::relay_policy`]; optional, since
+/// the policy does not affect consensus
+const TLV_RELAY_POLICY: u16 = 1;
+/// Extension record containing [`ChainParams::signet_challenge`]
+const TLV_SIGNET_CHALLENGE: u16 = 2;
+/// Extension record containing [`ChainParams::service_ports`]; optional
+const TLV_SERVICE_PORTS: u16 = 3;
+/// Extension record containing [`ChainParams::seeds`]; optional
+const TLV_SEEDS: u16 = 5;
+
+type Extensions = BTreeMap<u16, Vec<u8>>;
+
+fn put_extension(
+    extensions: &mut Extensions,
+    ty: u16,
+    value: &Option<impl StrictEncode>,
+) -> Result<(), strict_encoding::Error> {
+    if let Some(value) = value {
+        extensions.insert(ty, strict_serialize(value)?);
+    }
+    Ok(())
+}
+
+/// Liquid V1 shares P2P magic number with bitcoin regtest, which is decoded
+/// as [`P2pNetworkId::Regtest`]; chain format resolves this ambiguity
+fn resolve_p2p_magic(params: &mut ChainParams) {
+    if params.format == ChainFormat::Elements
+        && params.p2p_magic == P2pNetworkId::Regtest
+    {
+        params.p2p_magic = P2pNetworkId::LiquidV1;
+    }
+}
+
+/// Assembles extension records for the fields not included into the core
+/// part of the encoding
+fn collect_extensions(
+    params: &ChainParams,
+) -> Result<Extensions, strict_encoding::Error> {
+    let mut extensions = Extensions::new();
+    put_extension(&mut extensions, TLV_ELEMENTS, &params.elements)?;
+    put_extension(
+        &mut extensions,
+        TLV_RELAY_POLICY,
+        &Some(params.relay_policy),
+    )?;
+    put_extension(
+        &mut extensions,
+        TLV_SIGNET_CHALLENGE,
+        &params.signet_challenge,
+    )?;
+    put_extension(
+        &mut extensions,
+        TLV_SERVICE_PORTS,
+        &Some(params.service_ports),
+    )?;
+    if !params.seeds.is_empty() {
+        put_extension(&mut extensions, TLV_SEEDS, &Some(params.seeds.clone()))?;
+    }
+    Ok(extensions)
+}
+
+/// Decodes chain parameters from the legacy unversioned layout
+fn decode_legacy(
+    mut d: impl io::Read,
+) -> Result<ChainParams, strict_encoding::Error> {
+    let genesis_hash = BlockHash::strict_decode(&mut d)?;
+    let name = String::strict_decode(&mut d)?;
+    let p2p_magic = P2pNetworkId::strict_decode(&mut d)?;
+    let bip70_name = String::strict_decode(&mut d)?;
+    let bip173_prefix = String::strict_decode(&mut d)?;
+    let p2p_port = u16::strict_decode(&mut d)?;
+    let rpc_port = u16::strict_decode(&mut d)?;
+    let ln_height = u32::strict_decode(&mut d)?;
+    let rgb_height = u32::strict_decode(&mut d)?;
+    let format = ChainFormat::strict_decode(&mut d)?;
+    let dust_limit = u64::strict_decode(&mut d)?;
+    let native_asset = AssetParams::strict_decode(&mut d)?;
+    let is_testnet = bool::strict_decode(&mut d)?;
+    let is_pow = bool::strict_decode(&mut d)?;
+
+    let mut deployments = Deployments::new();
+    deployments.insert(Deployment::Lightning, ln_height);
+    deployments.insert(Deployment::Rgb, rgb_height);
+    let mut params = ChainParams {
+        genesis_hash,
+        name,
+        p2p_magic,
+        bip70_name,
+        bip173_prefix,
+        address_prefixes: if is_testnet {
+            AddressPrefixes::testnet()
+        } else {
+            AddressPrefixes::mainnet()
+        },
+        p2p_port,
+        rpc_port,
+        deployments,
+        format,
+        dust_limit,
+        native_asset,
+        is_testnet,
+        is_pow,
+        bip32_versions: if is_testnet {
+            Bip32Versions::TESTNET
+        } else {
+            Bip32Versions::MAINNET
+        },
+        economics: ChainEconomics::default(),
+        relay_policy: RelayPolicy::default(),
+        service_ports: ServicePorts::default(),
+        seeds: ChainSeeds::default(),
+        elements: None,
+        signet_challenge: None,
+    };
+    if let Some(chain) = Chain::from_genesis_hash(&genesis_hash) {
+        let known = chain.chain_params();
+        params.address_prefixes = known.address_prefixes;
+        params.deployments = known
+            .deployments
+            .into_iter()
+            .chain(params.deployments)
+            .collect();
+        params.bip32_versions = known.bip32_versions;
+        params.economics = known.economics;
+        params.relay_policy = known.relay_policy;
+        params.service_ports = known.service_ports;
+        params.seeds = known.seeds;
+        params.elements = known.elements;
+        params.signet_challenge = known.signet_challenge;
+    }
+    resolve_p2p_magic(&mut params);
+    Ok(params)
+}
+
+impl StrictEncode for ChainParams {
+    fn strict_encode<E: io::Write>(
+        &self,
+        mut e: E,
+    ) -> Result<usize, strict_encoding::Error> {
+        let extensions = collect_extensions(self)?;
+
+        e.write_all(&CHAIN_PARAMS_MAGIC)?;
+        let mut len = CHAIN_PARAMS_MAGIC.len();
+        Ok(strict_encode_list!(e; len;
+            CHAIN_PARAMS_VERSION,
+            self.genesis_hash,
+            self.name,
+            self.p2p_magic,
+            self.bip70_name,
+            self.bip173_prefix,
+            self.address_prefixes,
+            self.p2p_port,
+            self.rpc_port,
+            self.deployments,
+            self.format,
+            self.dust_limit,
+            self.native_asset,
+            self.is_testnet,
+            self.is_pow,
+            self.bip32_versions,
+            self.economics,
+            extensions
+        ))
+    }
+}
+
+impl StrictDecode for ChainParams {
+    fn strict_decode<D: io::Read>(
+        mut d: D,
+    ) -> Result<Self, strict_encoding::Error> {
+        let mut magic = [0u8; 4];
+        d.read_exact(&mut magic)?;
+        if magic != CHAIN_PARAMS_MAGIC {
+            // Legacy layout starts with genesis hash, which first bytes we
+            // have already read
+            return decode_legacy(magic.chain(d));
+        }
+
+        if u8::strict_decode(&mut d)? != CHAIN_PARAMS_VERSION {
+            return Err(strict_encoding::Error::UnsupportedDataStructure(
+                "chain parameters are encoded with unknown encoding version",
+            ));
+        }
+        let mut params = ChainParams {
+            genesis_hash: StrictDecode::strict_decode(&mut d)?,
+            name: StrictDecode::strict_decode(&mut d)?,
+            p2p_magic: StrictDecode::strict_decode(&mut d)?,
+            bip70_name: StrictDecode::strict_decode(&mut d)?,
+            bip173_prefix: StrictDecode::strict_decode(&mut d)?,
+            address_prefixes: StrictDecode::strict_decode(&mut d)?,
+            p2p_port: StrictDecode::strict_decode(&mut d)?,
+            rpc_port: StrictDecode::strict_decode(&mut d)?,
+            deployments: StrictDecode::strict_decode(&mut d)?,
+            format: StrictDecode::strict_decode(&mut d)?,
+            dust_limit: StrictDecode::strict_decode(&mut d)?,
+            native_asset: StrictDecode::strict_decode(&mut d)?,
+            is_testnet: StrictDecode::strict_decode(&mut d)?,
+            is_pow: StrictDecode::strict_decode(&mut d)?,
+            bip32_versions: StrictDecode::strict_decode(&mut d)?,
+            economics: StrictDecode::strict_decode(&mut d)?,
+            relay_policy: RelayPolicy::default(),
+            service_ports: ServicePorts::default(),
+            seeds: ChainSeeds::default(),
+            elements: None,
+            signet_challenge: None,
+        };
+        for (ty, value) in Extensions::strict_decode(&mut d)? {
+            match ty {
+                TLV_ELEMENTS => {
+                    params.elements = Some(strict_deserialize(value)?)
+                }
+                TLV_RELAY_POLICY => {
+                    params.relay_policy = strict_deserialize(value)?
+                }
+                TLV_SERVICE_PORTS => {
+                    params.service_ports = strict_deserialize(value)?
+                }
+                TLV_SEEDS => params.seeds = strict_deserialize(value)?,
+                TLV_SIGNET_CHALLENGE => {
+                    params.signet_challenge = Some(strict_deserialize(value)?)
+                }
+                ty if ty % 2 == 0 => {
+                    return Err(strict_encoding::Error::DataIntegrityError(
+                        format!(
+                            "unknown required chain parameters extension {}",
+                            ty
+                        ),
+                    ))
+                }
+                _ => {}
+            }
+        }
+        resolve_p2p_magic(&mut params);
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use strict_encoding::StrictEncode;
+
+    use super::*;
+    use crate::{Chain, CHAIN_PARAMS_LIQUIDV1, CHAIN_PARAMS_REGTEST};
+
+    /// Encodes parameters in the current layout with additional extensions
+    fn encode_with(params: &ChainParams, extra: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut extensions = collect_extensions(params).unwrap();
+        let mut data = strict_serialize(params).unwrap();
+        data.truncate(
+            data.len() - strict_serialize(&extensions).unwrap().len(),
+        );
+        for (ty, value) in extra {
+            extensions.insert(*ty, value.to_vec());
+        }
+        extensions.strict_encode(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn extensions() {
+        for chain in [Chain::Mainnet, Chain::Signet, Chain::LiquidV1] {
+            let params = chain.chain_params();
+            let data = encode_with(&params, &[]);
+            assert_eq!(data, strict_serialize(&params).unwrap());
+            assert_eq!(&data[..4], &CHAIN_PARAMS_MAGIC);
+            assert_eq!(data[4], CHAIN_PARAMS_VERSION);
+
+            // Unknown odd extensions are skipped
+            let data = encode_with(&params, &[(7, b"x"), (0xFFFF, b"")]);
+            let decoded: ChainParams = strict_deserialize(data).unwrap();
+            assert_eq!(
+                strict_serialize(&decoded).unwrap(),
+                strict_serialize(&params).unwrap()
+            );
+
+            // Unknown even extensions are rejected
+            let data = encode_with(&params, &[(0xFFFE, b"")]);
+            assert!(matches!(
+                strict_deserialize::<ChainParams>(data),
+                Err(strict_encoding::Error::DataIntegrityError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn liquid_magic() {
+        let data = strict_serialize(&*CHAIN_PARAMS_LIQUIDV1).unwrap();
+        let decoded: ChainParams = strict_deserialize(data).unwrap();
+        assert_eq!(decoded.p2p_magic, P2pNetworkId::LiquidV1);
+
+        let data = strict_serialize(&*CHAIN_PARAMS_REGTEST).unwrap();
+        let decoded: ChainParams = strict_deserialize(data).unwrap();
+        assert_eq!(decoded.p2p_magic, P2pNetworkId::Regtest);
+    }
+
+    #[test]
+    fn unknown_version() {
+        let mut data = strict_serialize(&*CHAIN_PARAMS_REGTEST).unwrap();
+        data[4] = CHAIN_PARAMS_VERSION + 1;
+        assert!(matches!(
+            strict_deserialize::<ChainParams>(data),
+            Err(strict_encoding::Error::UnsupportedDataStructure(_))
+        ));
+    }
+}
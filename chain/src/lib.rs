@@ -41,18 +41,75 @@ use std::{fmt, io};
 use bitcoin::hashes::hex::{self, FromHex, ToHex};
 use bitcoin::hashes::{sha256d, Hash};
 use bitcoin::network::constants::Network;
-use bitcoin::BlockHash;
+use bitcoin::{BlockHash, Script};
+use lnpbp_bech32::{FromBech32Str, ToBech32String};
 use once_cell::sync::Lazy;
 use strict_encoding::{
     strict_decode_self, strict_deserialize, strict_encode_list,
     strict_serialize, StrictDecode, StrictEncode,
 };
 
+mod address;
+mod amount;
+mod asset_id;
+mod assets;
+mod bip32;
+#[cfg(feature = "config")]
+mod config;
+mod deployment;
+mod economics;
+mod elements;
+mod encoding;
+mod lightning;
+mod p2p;
+mod policy;
+mod registry;
+mod seeds;
+mod services;
+mod signet;
+
+pub use address::{AddressError, AddressFormat, AddressPrefixes};
+pub use amount::{AmountError, AssetAmount, Rounding};
+#[cfg(feature = "serde")]
+pub use asset_id::serde_asset_id_backward;
+pub use asset_id::{AssetIdDisplay, AssetIdError};
+pub use assets::{AssetRegistry, AssetRegistryError, KnownAsset};
+pub use bip32::{
+    Bip32Version, Bip32Versions, KeyApplication, XkeyError, XkeyType,
+};
+#[cfg(feature = "config")]
+pub use config::{ChainDir, ConfigError, ConfigFormat, CHAIN_DIR_ENV};
+pub use deployment::{Deployment, Deployments};
+pub use economics::ChainEconomics;
+pub use elements::{
+    ElementsParams, POLICY_ASSET_LIQUIDV1, POLICY_ASSET_LIQUID_TESTNET,
+};
+pub use encoding::{CHAIN_PARAMS_MAGIC, CHAIN_PARAMS_VERSION};
+pub use lightning::{
+    ChainHash, BOLT12_HRP_INVOICE, BOLT12_HRP_INVOICE_REQUEST, BOLT12_HRP_OFFER,
+};
+pub use p2p::{P2pHeader, P2pHeaderError, P2P_HEADER_LEN};
+pub use policy::RelayPolicy;
+pub use registry::{ChainRegistry, RegistryError};
+pub use seeds::{ChainSeeds, SeedError};
+pub use services::{PortOverrides, Service, ServicePorts, UnknownService};
+pub use signet::{signet_magic, SIGNET_CHALLENGE_DEFAULT};
+
 /// P2P network magic number: prefix identifying network on which node operates
 pub type P2pMagicNumber = u32;
 /// Magic number prefixing Pubkey or Prvkey data according to BIP32 spec
 pub type Bip32MagicNumber = u32;
 
+/// P2P network magic number for bitcoin testnet4, which is not known to
+/// [`bitcoin::Network`]
+const P2P_MAGIC_TESTNET4: P2pMagicNumber = 0x283f161c;
+
+/// P2P network magic number for Liquid V1, matching bitcoin regtest one
+const P2P_MAGIC_LIQUIDV1: P2pMagicNumber = 0xdab5bffa;
+
+/// P2P network magic number for Liquid testnet
+const P2P_MAGIC_LIQUID_TESTNET: P2pMagicNumber = 0x62dd0e41;
+
 /// P2P network magic number: prefix identifying network on which node operates.
 /// This enum defines known magic network numbers, plus adds support to
 /// arbitrary unknown with [P2pNetworkId::Other] variant.
@@ -81,6 +138,18 @@ pub enum P2pNetworkId {
 
     /// Other magic number, implying some unknown network
     Other(P2pMagicNumber),
+
+    /// Bitcoin magic number for testnet4 P2P communications
+    Testnet4,
+
+    /// Liquid V1 magic number for P2P communications. Since it matches
+    /// bitcoin regtest magic number, [`P2pNetworkId::from_magic`] resolves it
+    /// into [`P2pNetworkId::Regtest`] (and so does the strict decoding); use
+    /// [`P2pNetworkId::all_from_magic`] to get both options.
+    LiquidV1,
+
+    /// Liquid testnet magic number for P2P communications
+    LiquidTestnet,
 }
 
 impl P2pNetworkId {
@@ -90,20 +159,53 @@ impl P2pNetworkId {
         match magic {
             m if m == P2pNetworkId::Mainnet.as_magic() => P2pNetworkId::Mainnet,
             m if m == P2pNetworkId::Testnet.as_magic() => P2pNetworkId::Testnet,
+            m if m == P2pNetworkId::Testnet4.as_magic() => {
+                P2pNetworkId::Testnet4
+            }
             m if m == P2pNetworkId::Regtest.as_magic() => P2pNetworkId::Regtest,
             m if m == P2pNetworkId::Signet.as_magic() => P2pNetworkId::Signet,
+            m if m == P2pNetworkId::LiquidTestnet.as_magic() => {
+                P2pNetworkId::LiquidTestnet
+            }
             m => P2pNetworkId::Other(m),
         }
     }
 
+    /// Returns all known networks using a given magic number. Unlike
+    /// [`P2pNetworkId::from_magic`], reports all networks sharing the same
+    /// magic number (like bitcoin regtest and Liquid V1).
+    pub fn all_from_magic(magic: P2pMagicNumber) -> Vec<P2pNetworkId> {
+        let known = [
+            P2pNetworkId::Mainnet,
+            P2pNetworkId::Testnet,
+            P2pNetworkId::Testnet4,
+            P2pNetworkId::Regtest,
+            P2pNetworkId::Signet,
+            P2pNetworkId::LiquidV1,
+            P2pNetworkId::LiquidTestnet,
+        ];
+        let networks = known
+            .into_iter()
+            .filter(|network| network.as_magic() == magic)
+            .collect::<Vec<_>>();
+        if networks.is_empty() {
+            vec![P2pNetworkId::Other(magic)]
+        } else {
+            networks
+        }
+    }
+
     /// Returns representation in form of network magic number
     /// [`P2pMagicNumber`]
     pub fn as_magic(&self) -> P2pMagicNumber {
         match self {
             P2pNetworkId::Mainnet => Network::Bitcoin.magic(),
             P2pNetworkId::Testnet => Network::Testnet.magic(),
+            P2pNetworkId::Testnet4 => P2P_MAGIC_TESTNET4,
             P2pNetworkId::Regtest => Network::Regtest.magic(),
             P2pNetworkId::Signet => Network::Signet.magic(),
+            P2pNetworkId::LiquidV1 => P2P_MAGIC_LIQUIDV1,
+            P2pNetworkId::LiquidTestnet => P2P_MAGIC_LIQUID_TESTNET,
             P2pNetworkId::Other(n) => *n,
         }
     }
@@ -119,6 +221,10 @@ impl StrictEncode for P2pNetworkId {
     }
 }
 
+/// Network id is strict-encoded as its magic number, so decoding does not
+/// round-trip [`P2pNetworkId::LiquidV1`], which shares the magic number with
+/// bitcoin regtest and is decoded as [`P2pNetworkId::Regtest`]. Decoding of
+/// [`ChainParams`] recovers Liquid V1 from [`ChainFormat::Elements`].
 impl StrictDecode for P2pNetworkId {
     #[inline]
     fn strict_decode<D: io::Read>(
@@ -139,8 +245,11 @@ impl Display for P2pNetworkId {
         match self {
             P2pNetworkId::Mainnet => f.write_str("mainnet"),
             P2pNetworkId::Testnet => f.write_str("testnet"),
+            P2pNetworkId::Testnet4 => f.write_str("testnet4"),
             P2pNetworkId::Regtest => f.write_str("regtest"),
             P2pNetworkId::Signet => f.write_str("signet"),
+            P2pNetworkId::LiquidV1 => f.write_str("liquidv1"),
+            P2pNetworkId::LiquidTestnet => f.write_str("liquidtestnet"),
             P2pNetworkId::Other(_) => f.write_str("unknown"),
         }
     }
@@ -248,6 +357,10 @@ impl NativeAsset for Option<AssetId> {
     }
 }
 
+/// Bech32 HRP used by compressed string representation of custom chain
+/// parameters (see [`Chain::Other`])
+pub const HRP_CHAIN: &str = "chain";
+
 /// Genesis block hash for bitcoin mainnet
 pub const GENESIS_HASH_MAINNET: &[u8] = &[
     0x6f, 0xe2, 0x8c, 0x0a, 0xb6, 0xf1, 0xb3, 0x72, 0xc1, 0xa6, 0xa2, 0x46,
@@ -262,6 +375,13 @@ pub const GENESIS_HASH_TESTNET: &[u8] = &[
     0x01, 0xea, 0x33, 0x09, 0x00, 0x00, 0x00, 0x00,
 ];
 
+/// Genesis block hash for bitcoin testnet v4
+pub const GENESIS_HASH_TESTNET4: &[u8] = &[
+    0x43, 0xf0, 0x8b, 0xda, 0xb0, 0x50, 0xe3, 0x5b, 0x56, 0x7c, 0x86, 0x4b,
+    0x91, 0xf4, 0x7f, 0x50, 0xae, 0x72, 0x5a, 0xe2, 0xde, 0x53, 0xbc, 0xfb,
+    0xba, 0xf2, 0x84, 0xda, 0x00, 0x00, 0x00, 0x00,
+];
+
 /// Genesis block hash for bitcoin regtest network(s)
 pub const GENESIS_HASH_REGTEST: &[u8] = &[
     0x06, 0x22, 0x6e, 0x46, 0x11, 0x1a, 0x0b, 0x59, 0xca, 0xaf, 0x12, 0x60,
@@ -283,6 +403,13 @@ pub const GENESIS_HASH_LIQUIDV1: &[u8] = &[
     0x37, 0x92, 0x96, 0x88, 0x8a, 0x20, 0x60, 0x03,
 ];
 
+/// Genesis block hash for liquid testnet sidechain
+pub const GENESIS_HASH_LIQUID_TESTNET: &[u8] = &[
+    0xc1, 0xb1, 0x6a, 0xe2, 0x4f, 0x24, 0x23, 0xae, 0xa2, 0xea, 0x34, 0x55,
+    0x22, 0x92, 0x79, 0x3b, 0x5b, 0x5e, 0x82, 0x99, 0x9a, 0x1e, 0xed, 0x81,
+    0xd5, 0x6a, 0xee, 0x52, 0x8e, 0xda, 0x71, 0xa7,
+];
+
 /// Bitcoin mainnet chain parameters
 static CHAIN_PARAMS_MAINNET: Lazy<ChainParams> = Lazy::new(|| {
     ChainParams {
@@ -292,13 +419,22 @@ static CHAIN_PARAMS_MAINNET: Lazy<ChainParams> = Lazy::new(|| {
             .expect("Bitcoin genesis hash contains invalid binary data"),
         bip70_name: "main".to_string(),
         bip173_prefix: "bc".to_string(),
+        address_prefixes: AddressPrefixes::mainnet(),
         p2p_port: 8333,
         rpc_port: 8332,
-        ln_height: 504500,
-        // TODO #217: update with first RGB release
-        rgb_height: 650000,
+        deployments: bmap! {
+            Deployment::Bip34 => 227_931,
+            Deployment::Bip65 => 388_381,
+            Deployment::Bip66 => 363_725,
+            Deployment::Csv => 419_328,
+            Deployment::Segwit => 481_824,
+            Deployment::Taproot => 709_632,
+            Deployment::Lightning => 504_500,
+            // TODO #217: update with first RGB release
+            Deployment::Rgb => 650_000
+        },
         format: ChainFormat::Bitcoin,
-        dust_limit: 546,
+        dust_limit: RelayPolicy::BITCOIN_CORE.p2pkh_dust_limit(),
         native_asset: AssetParams {
             ticker: "BTC".to_string(),
             unit_of_accounting: "Bitcoin".to_string(),
@@ -310,6 +446,13 @@ static CHAIN_PARAMS_MAINNET: Lazy<ChainParams> = Lazy::new(|| {
         },
         is_testnet: false,
         is_pow: true,
+        bip32_versions: Bip32Versions::MAINNET,
+        economics: ChainEconomics::MAINNET,
+        relay_policy: RelayPolicy::BITCOIN_CORE,
+        service_ports: ServicePorts::MAINNET,
+        seeds: ChainSeeds::mainnet(),
+        elements: None,
+        signet_challenge: None,
     }
 });
 
@@ -323,13 +466,22 @@ static CHAIN_PARAMS_TESTNET: Lazy<ChainParams> = Lazy::new(|| {
         ),
         bip70_name: "test".to_string(),
         bip173_prefix: "tb".to_string(),
+        address_prefixes: AddressPrefixes::testnet(),
         p2p_port: 18333,
         rpc_port: 18332,
-        ln_height: 1,
-        // TODO #217: update with first RGB release
-        rgb_height: 1835500,
+        deployments: bmap! {
+            Deployment::Bip34 => 21_111,
+            Deployment::Bip65 => 581_885,
+            Deployment::Bip66 => 330_776,
+            Deployment::Csv => 770_112,
+            Deployment::Segwit => 834_624,
+            Deployment::Taproot => 2_011_968,
+            Deployment::Lightning => 1,
+            // TODO #217: update with first RGB release
+            Deployment::Rgb => 1_835_500
+        },
         format: ChainFormat::Bitcoin,
-        dust_limit: 546,
+        dust_limit: RelayPolicy::BITCOIN_CORE.p2pkh_dust_limit(),
         native_asset: AssetParams {
             ticker: "tBTC".to_string(),
             unit_of_accounting: "Test Bitcoin".to_string(),
@@ -342,9 +494,60 @@ static CHAIN_PARAMS_TESTNET: Lazy<ChainParams> = Lazy::new(|| {
         },
         is_testnet: true,
         is_pow: true,
+        bip32_versions: Bip32Versions::TESTNET,
+        economics: ChainEconomics::TESTNET,
+        relay_policy: RelayPolicy::BITCOIN_CORE,
+        service_ports: ServicePorts::TESTNET,
+        seeds: ChainSeeds::testnet(),
+        elements: None,
+        signet_challenge: None,
     }
 });
 
+/// Bitcoin testnet4 chain parameters
+static CHAIN_PARAMS_TESTNET4: Lazy<ChainParams> = Lazy::new(|| ChainParams {
+    name: "testnet4".to_string(),
+    p2p_magic: P2pNetworkId::Testnet4,
+    genesis_hash: BlockHash::from_slice(GENESIS_HASH_TESTNET4)
+        .expect("Bitcoin testnet4 genesis hash contains invalid binary data"),
+    bip70_name: "testnet4".to_string(),
+    bip173_prefix: "tb".to_string(),
+    address_prefixes: AddressPrefixes::testnet(),
+    p2p_port: 48333,
+    rpc_port: 48332,
+    deployments: bmap! {
+        Deployment::Bip34 => 1,
+        Deployment::Bip65 => 1,
+        Deployment::Bip66 => 1,
+        Deployment::Csv => 1,
+        Deployment::Segwit => 1,
+        Deployment::Taproot => 0,
+        Deployment::Lightning => 1,
+        Deployment::Rgb => 1
+    },
+    format: ChainFormat::Bitcoin,
+    dust_limit: RelayPolicy::BITCOIN_CORE.p2pkh_dust_limit(),
+    native_asset: AssetParams {
+        ticker: "tBTC".to_string(),
+        unit_of_accounting: "Test Bitcoin".to_string(),
+        indivisible_unit: "Test satoshi".to_string(),
+        divisibility: 100_000_000,
+        asset_id: AssetId::from_slice(GENESIS_HASH_TESTNET4).expect(
+            "Bitcoin testnet4 genesis hash contains invalid binary data",
+        ),
+        asset_system: AssetSystem::NativeBlockchain,
+    },
+    is_testnet: true,
+    is_pow: true,
+    bip32_versions: Bip32Versions::TESTNET,
+    economics: ChainEconomics::TESTNET4,
+    relay_policy: RelayPolicy::BITCOIN_CORE,
+    service_ports: ServicePorts::TESTNET4,
+    seeds: ChainSeeds::testnet4(),
+    elements: None,
+    signet_challenge: None,
+});
+
 /// Bitcoin regtest chain parameters
 static CHAIN_PARAMS_REGTEST: Lazy<ChainParams> = Lazy::new(|| ChainParams {
     name: "regtest".to_string(),
@@ -353,12 +556,21 @@ static CHAIN_PARAMS_REGTEST: Lazy<ChainParams> = Lazy::new(|| ChainParams {
         .expect("Bitcoin regtest genesis hash contains invalid binary data"),
     bip70_name: "regtest".to_string(),
     bip173_prefix: "bcrt".to_string(),
+    address_prefixes: AddressPrefixes::testnet(),
     p2p_port: 28333,
     rpc_port: 28332,
-    ln_height: 1,
-    rgb_height: 1,
+    deployments: bmap! {
+        Deployment::Bip34 => 1,
+        Deployment::Bip65 => 1,
+        Deployment::Bip66 => 1,
+        Deployment::Csv => 1,
+        Deployment::Segwit => 0,
+        Deployment::Taproot => 0,
+        Deployment::Lightning => 1,
+        Deployment::Rgb => 1
+    },
     format: ChainFormat::Bitcoin,
-    dust_limit: 546,
+    dust_limit: RelayPolicy::BITCOIN_CORE.p2pkh_dust_limit(),
     native_asset: AssetParams {
         ticker: "tBTC".to_string(),
         unit_of_accounting: "Test Bitcoin".to_string(),
@@ -371,6 +583,13 @@ static CHAIN_PARAMS_REGTEST: Lazy<ChainParams> = Lazy::new(|| ChainParams {
     },
     is_testnet: true,
     is_pow: false,
+    bip32_versions: Bip32Versions::TESTNET,
+    economics: ChainEconomics::REGTEST,
+    relay_policy: RelayPolicy::BITCOIN_CORE,
+    service_ports: ServicePorts::REGTEST,
+    seeds: ChainSeeds::default(),
+    elements: None,
+    signet_challenge: None,
 });
 
 /// Bitcoin signet chain parameters
@@ -381,12 +600,21 @@ static CHAIN_PARAMS_SIGNET: Lazy<ChainParams> = Lazy::new(|| ChainParams {
         .expect("Bitcoin signet genesis hash contains invalid binary data"),
     bip70_name: "signet".to_string(),
     bip173_prefix: "tb".to_string(),
+    address_prefixes: AddressPrefixes::testnet(),
     p2p_port: 38333,
     rpc_port: 38332,
-    ln_height: 1,
-    rgb_height: 1,
+    deployments: bmap! {
+        Deployment::Bip34 => 1,
+        Deployment::Bip65 => 1,
+        Deployment::Bip66 => 1,
+        Deployment::Csv => 1,
+        Deployment::Segwit => 1,
+        Deployment::Taproot => 0,
+        Deployment::Lightning => 1,
+        Deployment::Rgb => 1
+    },
     format: ChainFormat::Bitcoin,
-    dust_limit: 546,
+    dust_limit: RelayPolicy::BITCOIN_CORE.p2pkh_dust_limit(),
     native_asset: AssetParams {
         ticker: "sBTC".to_string(),
         unit_of_accounting: "Signet Bitcoin".to_string(),
@@ -398,25 +626,45 @@ static CHAIN_PARAMS_SIGNET: Lazy<ChainParams> = Lazy::new(|| ChainParams {
     },
     is_testnet: true,
     is_pow: false,
+    bip32_versions: Bip32Versions::TESTNET,
+    economics: ChainEconomics::SIGNET,
+    relay_policy: RelayPolicy::BITCOIN_CORE,
+    service_ports: ServicePorts::SIGNET,
+    seeds: ChainSeeds::signet(),
+    elements: None,
+    signet_challenge: Some(Script::from(SIGNET_CHALLENGE_DEFAULT.to_vec())),
 });
 
 /// Liquid V1 chain parameters
 static CHAIN_PARAMS_LIQUIDV1: Lazy<ChainParams> = Lazy::new(|| {
     ChainParams {
         name: "liquidv1".to_string(),
-        // TODO #216: check Liquid network magic number and change this if
-        // needed
-        p2p_magic: P2pNetworkId::Mainnet,
+        p2p_magic: P2pNetworkId::LiquidV1,
         genesis_hash: BlockHash::from_slice(GENESIS_HASH_LIQUIDV1)
             .expect("Liquid V1 genesis hash contains invalid binary data"),
         bip70_name: "liquidv1".to_string(),
         bip173_prefix: "ex".to_string(),
+        address_prefixes: AddressPrefixes {
+            p2pkh: 57,
+            p2sh: 39,
+            confidential: Some(12),
+        },
         p2p_port: 7042,
         rpc_port: 7041,
-        ln_height: 1,
-        rgb_height: 1_000_000,
+        deployments: bmap! {
+            Deployment::Bip34 => 0,
+            Deployment::Bip65 => 0,
+            Deployment::Bip66 => 0,
+            Deployment::Csv => 0,
+            Deployment::Segwit => 0,
+            // Taproot is activated on Liquid through BIP9 signalling at a
+            // height which is not tracked yet
+            Deployment::Taproot => Deployment::UNKNOWN_HEIGHT,
+            Deployment::Lightning => 1,
+            Deployment::Rgb => 1_000_000
+        },
         format: ChainFormat::Elements,
-        dust_limit: 546,
+        dust_limit: RelayPolicy::LIQUIDV1.p2pkh_dust_limit(),
         native_asset: AssetParams {
             ticker: "LBTC".to_string(),
             unit_of_accounting: "Liquid Bitcoin".to_string(),
@@ -428,9 +676,79 @@ static CHAIN_PARAMS_LIQUIDV1: Lazy<ChainParams> = Lazy::new(|| {
         },
         is_testnet: false,
         is_pow: false,
+        bip32_versions: Bip32Versions::MAINNET,
+        economics: ChainEconomics::LIQUIDV1,
+        relay_policy: RelayPolicy::LIQUIDV1,
+        service_ports: ServicePorts::LIQUIDV1,
+        seeds: ChainSeeds::default(),
+        elements: Some(ElementsParams {
+            pegin_confirmation_depth: 102,
+            fedpeg_script: None,
+            policy_asset: AssetId::from_slice(POLICY_ASSET_LIQUIDV1)
+                .expect("Liquid V1 policy asset contains invalid binary data"),
+            blinded_hrp: "lq".to_string(),
+        }),
+        signet_challenge: None,
     }
 });
 
+/// Liquid testnet chain parameters
+static CHAIN_PARAMS_LIQUID_TESTNET: Lazy<ChainParams> =
+    Lazy::new(|| ChainParams {
+        name: "liquidtestnet".to_string(),
+        p2p_magic: P2pNetworkId::LiquidTestnet,
+        genesis_hash: BlockHash::from_slice(GENESIS_HASH_LIQUID_TESTNET)
+            .expect("Liquid testnet genesis hash contains invalid binary data"),
+        bip70_name: "liquidtestnet".to_string(),
+        bip173_prefix: "tex".to_string(),
+        address_prefixes: AddressPrefixes {
+            p2pkh: 36,
+            p2sh: 19,
+            confidential: Some(23),
+        },
+        p2p_port: 18891,
+        rpc_port: 18892,
+        deployments: bmap! {
+            Deployment::Bip34 => 0,
+            Deployment::Bip65 => 0,
+            Deployment::Bip66 => 0,
+            Deployment::Csv => 0,
+            Deployment::Segwit => 0,
+            Deployment::Taproot => Deployment::UNKNOWN_HEIGHT,
+            Deployment::Lightning => 1,
+            Deployment::Rgb => 1
+        },
+        format: ChainFormat::Elements,
+        dust_limit: RelayPolicy::LIQUIDV1.p2pkh_dust_limit(),
+        native_asset: AssetParams {
+            ticker: "tLBTC".to_string(),
+            unit_of_accounting: "Liquid Testnet Bitcoin".to_string(),
+            indivisible_unit: "Liquid testnet satoshi".to_string(),
+            divisibility: 100_000_000,
+            asset_id: AssetId::from_slice(GENESIS_HASH_LIQUID_TESTNET).expect(
+                "Liquid testnet genesis hash contains invalid binary data",
+            ),
+            asset_system: AssetSystem::NativeBlockchain,
+        },
+        is_testnet: true,
+        is_pow: false,
+        bip32_versions: Bip32Versions::TESTNET,
+        economics: ChainEconomics::LIQUIDV1,
+        relay_policy: RelayPolicy::LIQUIDV1,
+        service_ports: ServicePorts::LIQUID_TESTNET,
+        seeds: ChainSeeds::default(),
+        elements: Some(ElementsParams {
+            pegin_confirmation_depth: 102,
+            fedpeg_script: None,
+            policy_asset: AssetId::from_slice(POLICY_ASSET_LIQUID_TESTNET)
+                .expect(
+                    "Liquid testnet policy asset contains invalid binary data",
+                ),
+            blinded_hrp: "tlq".to_string(),
+        }),
+        signet_challenge: None,
+    });
+
 /// Enum identifying format for transaction & block structure in a given chain.
 /// Right now only two structures are supported: Bitcoin format and
 /// Elements format, extended with confidential transaction-specific structures.
@@ -512,7 +830,7 @@ pub enum AssetSystem {
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
-    serde(crate = "serde_crate", rename_all = "kebab-case")
+    serde(crate = "serde_crate", deny_unknown_fields)
 )]
 #[display(Debug)]
 pub struct AssetParams {
@@ -554,10 +872,7 @@ impl PartialEq for AssetParams {
 impl Eq for AssetParams {}
 
 impl std::hash::Hash for AssetParams {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write(&self.asset_id);
-        state.finish();
-    }
+    fn hash<H: Hasher>(&self, state: &mut H) { state.write(&self.asset_id); }
 }
 
 impl StrictEncode for AssetParams {
@@ -595,11 +910,11 @@ impl StrictDecode for AssetParams {
 
 /// Full set of parameters which uniquely define given blockchain,
 /// corresponding P2P network and RPC interface of fully validating nodes
-#[derive(Clone, PartialOrd, Ord, Debug, Display, StrictEncode, StrictDecode)]
+#[derive(Clone, PartialOrd, Ord, Debug, Display)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
-    serde(crate = "serde_crate")
+    serde(crate = "serde_crate", deny_unknown_fields)
 )]
 #[display(Debug)]
 pub struct ChainParams {
@@ -625,23 +940,27 @@ pub struct ChainParams {
     /// HRP bech32 address prefix as defined in BIP 173
     pub bip173_prefix: String,
 
+    /// Base58 address version bytes and confidential address prefixes
+    pub address_prefixes: AddressPrefixes,
+
     /// Default port for P2P network
     pub p2p_port: u16,
 
     /// Default port for full validating node RPC interface
     pub rpc_port: u16,
 
-    /// Block number from which Lightning network support had started using
-    /// the given chain
-    pub ln_height: u32,
-
-    /// Block number from which RGB had started using the given chain
-    pub rgb_height: u32,
+    /// Activation heights of soft-forks and protocols (including Lightning
+    /// network and RGB) on the given chain
+    pub deployments: Deployments,
 
     /// Format of chain-specific data. See [ChainFormat] for more information
     pub format: ChainFormat,
 
-    /// Dust limit for the given chain; 0 if none dust limit applies
+    /// Dust limit for the given chain; 0 if none dust limit applies. This is
+    /// the threshold for P2PKH outputs derived from the
+    /// [`ChainParams::relay_policy`] (see [`RelayPolicy::p2pkh_dust_limit`]),
+    /// and it must match the policy; thresholds for other output types are
+    /// provided by [`ChainParams::dust_limit_for`].
     pub dust_limit: u64,
 
     /// Parameters of the native chain asset (can be only one; it is the asset
@@ -654,11 +973,70 @@ pub struct ChainParams {
 
     /// Flag indicating blockchains that use PoW consensus algorithm
     pub is_pow: bool,
+
+    /// BIP32 magic numbers (SLIP-132 version bytes) used for extended keys
+    pub bip32_versions: Bip32Versions,
+
+    /// Subsidy schedule and block timing
+    pub economics: ChainEconomics,
+
+    /// Transaction relay policy of the chain nodes; defaults to Bitcoin Core
+    /// policy if not provided
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub relay_policy: RelayPolicy,
+
+    /// Default ports of the network services operating on the chain; all
+    /// zeros (no defaults) if not provided
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub service_ports: ServicePorts,
+
+    /// DNS seeds and fixed seed addresses used to bootstrap P2P connections;
+    /// empty if not provided
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seeds: ChainSeeds,
+
+    /// Parameters specific for chains using [`ChainFormat::Elements`] format
+    pub elements: Option<ElementsParams>,
+
+    /// Challenge script for signet chains (BIP325), or `None` for other
+    /// chains. Since all signets share the same genesis block, the challenge
+    /// is what distinguishes them.
+    pub signet_challenge: Option<Script>,
+}
+
+// Strict-encoded parameters are compressed and protected with bech32m
+// checksum, which makes `chain1...` string much shorter than the hex
+impl lnpbp_bech32::Strategy for ChainParams {
+    const HRP: &'static str = HRP_CHAIN;
+    type Strategy = lnpbp_bech32::strategies::CompressedStrictEncoding;
+}
+
+/// Returns signet challenge identifying the chain in comparisons. Chains on
+/// the signet genesis block which do not specify the challenge are treated as
+/// the default signet; for the rest of chains a missing challenge is a value
+/// on its own, which keeps the order total.
+fn signet_challenge_key<'a>(
+    genesis_hash: &BlockHash,
+    challenge: Option<&'a Script>,
+) -> Option<&'a Script> {
+    match challenge {
+        None if &genesis_hash[..] == GENESIS_HASH_SIGNET => {
+            CHAIN_PARAMS_SIGNET.signet_challenge.as_ref()
+        }
+        challenge => challenge,
+    }
 }
 
 impl PartialEq for ChainParams {
     fn eq(&self, other: &Self) -> bool {
         self.genesis_hash == other.genesis_hash
+            && signet_challenge_key(
+                &self.genesis_hash,
+                self.signet_challenge.as_ref(),
+            ) == signet_challenge_key(
+                &other.genesis_hash,
+                other.signet_challenge.as_ref(),
+            )
     }
 }
 
@@ -667,7 +1045,6 @@ impl Eq for ChainParams {}
 impl std::hash::Hash for ChainParams {
     fn hash<H: Hasher>(&self, state: &mut H) {
         state.write(&self.genesis_hash);
-        state.finish();
     }
 }
 
@@ -690,6 +1067,10 @@ pub enum Chain {
     #[cfg_attr(feature = "serde", serde(rename = "testnet"))]
     Testnet3,
 
+    /// Bitcoin testnet version 4 (BIP94)
+    #[cfg_attr(feature = "serde", serde(rename = "testnet4"))]
+    Testnet4,
+
     /// Bitcoin regtest network, with provided genesis hash to distinguish
     /// different private networks
     #[cfg_attr(feature = "serde", serde(rename = "regtest"))]
@@ -704,43 +1085,65 @@ pub enum Chain {
     #[cfg_attr(feature = "serde", serde(rename = "signetCustom"))]
     SignetCustom(BlockHash),
 
+    /// Private bitcoin signet network defined by its challenge script (the
+    /// value of bitcoind `-signetchallenge` argument)
+    #[cfg_attr(feature = "serde", serde(rename = "signetChallenge"))]
+    SignetChallenge(Script),
+
     /// Liquidv1 sidechain & network by Blockstream
     #[cfg_attr(feature = "serde", serde(rename = "liquidv1"))]
     LiquidV1,
 
+    /// Liquid testnet sidechain & network by Blockstream
+    #[cfg_attr(feature = "serde", serde(rename = "liquidtestnet"))]
+    LiquidTestnet,
+
     /// All other networks/chains, providing full information on chain
     /// parameters
     #[cfg_attr(feature = "serde", serde(rename = "custom"))]
     Other(Box<ChainParams>),
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Chain {
     fn default() -> Self { Chain::Signet }
 }
 
+// Comparison and hashing use only borrowed genesis hash and signet challenge
+// (the same data as used by `ChainParams`), which does not require cloning
+// chain parameters
 impl PartialEq for Chain {
-    fn eq(&self, other: &Self) -> bool {
-        self.chain_params().eq(&other.chain_params())
-    }
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
 }
 
 impl Eq for Chain {}
 
 impl PartialOrd for Chain {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.chain_params().partial_cmp(&other.chain_params())
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Chain {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.chain_params().cmp(&other.chain_params())
+        self.as_genesis_hash()
+            .cmp(other.as_genesis_hash())
+            .then_with(|| {
+                signet_challenge_key(
+                    self.as_genesis_hash(),
+                    self.as_signet_challenge(),
+                )
+                .cmp(&signet_challenge_key(
+                    other.as_genesis_hash(),
+                    other.as_signet_challenge(),
+                ))
+            })
     }
 }
 
 impl std::hash::Hash for Chain {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.chain_params().hash(state);
+        state.write(self.as_genesis_hash());
     }
 }
 
@@ -750,28 +1153,66 @@ impl Chain {
         &[
             Chain::Mainnet,
             Chain::Testnet3,
+            Chain::Testnet4,
             Chain::Signet,
             Chain::LiquidV1,
+            Chain::LiquidTestnet,
         ]
     }
 
+    /// Enumerates all built-in chains: [`Chain::all_standard`] chains followed
+    /// by regtest with the default genesis
+    pub fn builtin() -> Vec<Chain> {
+        let mut chains = Chain::all_standard().to_vec();
+        chains.push(Chain::Regtest(CHAIN_PARAMS_REGTEST.genesis_hash));
+        chains
+    }
+
+    /// Enumerates all chains known to the process: [`Chain::builtin`] chains
+    /// followed by chains registered with [`ChainRegistry`], which are
+    /// returned as [`Chain::Other`]
+    pub fn known() -> Vec<Chain> {
+        let mut chains = Chain::builtin();
+        chains.extend(
+            ChainRegistry::list()
+                .into_iter()
+                .map(|params| Chain::Other(Box::new(params))),
+        );
+        chains
+    }
+
     /// Returns chain parameters [ChainParams] for a given chain id
     pub fn chain_params(&self) -> ChainParams {
         match self {
             Chain::Mainnet => CHAIN_PARAMS_MAINNET.clone(),
             Chain::Testnet3 => CHAIN_PARAMS_TESTNET.clone(),
+            Chain::Testnet4 => CHAIN_PARAMS_TESTNET4.clone(),
             Chain::Regtest(hash) => {
                 let mut regtest = CHAIN_PARAMS_REGTEST.clone();
                 regtest.genesis_hash = *hash;
                 regtest
             }
             Chain::Signet => CHAIN_PARAMS_SIGNET.clone(),
+            // Nodes of the default signet can't bootstrap custom signets, so
+            // like Bitcoin Core we do not provide seeds for them. Challenge of
+            // such signets is unknown.
             Chain::SignetCustom(hash) => {
                 let mut signet = CHAIN_PARAMS_SIGNET.clone();
                 signet.genesis_hash = *hash;
+                signet.signet_challenge = None;
+                signet.seeds = ChainSeeds::default();
+                signet
+            }
+            Chain::SignetChallenge(challenge) => {
+                let mut signet = CHAIN_PARAMS_SIGNET.clone();
+                signet.p2p_magic =
+                    P2pNetworkId::with_signet_challenge(challenge);
+                signet.signet_challenge = Some(challenge.clone());
+                signet.seeds = ChainSeeds::default();
                 signet
             }
             Chain::LiquidV1 => CHAIN_PARAMS_LIQUIDV1.clone(),
+            Chain::LiquidTestnet => CHAIN_PARAMS_LIQUID_TESTNET.clone(),
             Chain::Other(params) => params.as_ref().clone(),
         }
     }
@@ -781,24 +1222,31 @@ impl Chain {
         match self {
             Chain::Mainnet => &CHAIN_PARAMS_MAINNET.genesis_hash,
             Chain::Testnet3 => &CHAIN_PARAMS_TESTNET.genesis_hash,
+            Chain::Testnet4 => &CHAIN_PARAMS_TESTNET4.genesis_hash,
             Chain::Regtest(hash) => hash,
             Chain::Signet => &CHAIN_PARAMS_SIGNET.genesis_hash,
             Chain::SignetCustom(hash) => hash,
+            Chain::SignetChallenge(_) => &CHAIN_PARAMS_SIGNET.genesis_hash,
             Chain::LiquidV1 => &CHAIN_PARAMS_LIQUIDV1.genesis_hash,
+            Chain::LiquidTestnet => &CHAIN_PARAMS_LIQUID_TESTNET.genesis_hash,
             Chain::Other(params) => &params.genesis_hash,
         }
     }
 
     /// Gueses chain from the given genesis block hash, returning
-    /// [Option::None] if the hash is unknown. This implies that for
-    /// custom signet and some regtest networks with modified genesis the
-    /// function will fail.
+    /// [Option::None] if the hash is unknown. Chains registered with
+    /// [`ChainRegistry`] are checked after the built-in ones and returned as
+    /// [`Chain::Other`]; for other custom signet and regtest networks with
+    /// modified genesis the function will fail.
     pub fn from_genesis_hash(hash: &BlockHash) -> Option<Self> {
         match hash {
             h if *h == CHAIN_PARAMS_MAINNET.genesis_hash => Some(Self::Mainnet),
             h if *h == CHAIN_PARAMS_TESTNET.genesis_hash => {
                 Some(Self::Testnet3)
             }
+            h if *h == CHAIN_PARAMS_TESTNET4.genesis_hash => {
+                Some(Self::Testnet4)
+            }
             h if *h == CHAIN_PARAMS_SIGNET.genesis_hash => Some(Self::Signet),
             h if *h == CHAIN_PARAMS_REGTEST.genesis_hash => {
                 Some(Self::Regtest(*h))
@@ -806,7 +1254,11 @@ impl Chain {
             h if *h == CHAIN_PARAMS_LIQUIDV1.genesis_hash => {
                 Some(Self::LiquidV1)
             }
-            _ => None,
+            h if *h == CHAIN_PARAMS_LIQUID_TESTNET.genesis_hash => {
+                Some(Self::LiquidTestnet)
+            }
+            h => ChainRegistry::by_genesis_hash(h)
+                .map(|params| Self::Other(Box::new(params))),
         }
     }
 
@@ -843,8 +1295,20 @@ impl From<ChainParams> for Chain {
         match params {
             p if p == Chain::Mainnet.chain_params() => Chain::Mainnet,
             p if p == Chain::Testnet3.chain_params() => Chain::Testnet3,
+            p if p == Chain::Testnet4.chain_params() => Chain::Testnet4,
             p if p == Chain::Signet.chain_params() => Chain::Signet,
             p if p == Chain::LiquidV1.chain_params() => Chain::LiquidV1,
+            p if p == Chain::LiquidTestnet.chain_params() => {
+                Chain::LiquidTestnet
+            }
+            ChainParams {
+                signet_challenge: Some(ref challenge),
+                ..
+            } if params
+                == Chain::SignetChallenge(challenge.clone()).chain_params() =>
+            {
+                Chain::SignetChallenge(challenge.clone())
+            }
             p if p == Chain::Regtest(p.genesis_hash).chain_params() => {
                 Chain::Regtest(p.genesis_hash)
             }
@@ -898,6 +1362,7 @@ impl Display for Chain {
         match self {
             Chain::Mainnet => write!(f, "bitcoin"),
             Chain::Testnet3 => write!(f, "testnet"),
+            Chain::Testnet4 => write!(f, "testnet4"),
             Chain::Regtest(hash) if &hash[..] == GENESIS_HASH_REGTEST => {
                 write!(f, "regtest")
             }
@@ -907,52 +1372,61 @@ impl Display for Chain {
                 write!(f, "signet")
             }
             Chain::SignetCustom(hash) => write!(f, "signet:{}", hash),
-            Chain::LiquidV1 => write!(f, "liquidv1"),
-            Chain::Other(params)
-                if &params.genesis_hash[..] == GENESIS_HASH_MAINNET =>
-            {
-                write!(f, "bitcoin")
-            }
-            Chain::Other(params)
-                if &params.genesis_hash[..] == GENESIS_HASH_TESTNET =>
-            {
-                write!(f, "testnet")
-            }
-            Chain::Other(params)
-                if &params.genesis_hash[..] == GENESIS_HASH_REGTEST =>
-            {
-                write!(f, "regtest")
-            }
-            Chain::Other(params)
-                if &params.genesis_hash[..] == GENESIS_HASH_SIGNET =>
+            Chain::SignetChallenge(challenge)
+                if challenge.as_bytes() == SIGNET_CHALLENGE_DEFAULT =>
             {
                 write!(f, "signet")
             }
-            Chain::Other(params)
-                if &params.genesis_hash[..] == GENESIS_HASH_LIQUIDV1 =>
-            {
-                write!(f, "liquidv1")
-            }
-            Chain::Other(params) => {
-                write!(
-                    f,
-                    "other:{}",
-                    strict_serialize(params.as_ref())?.to_hex()
-                )
+            Chain::SignetChallenge(challenge) => {
+                write!(f, "signetchallenge:{}", challenge.to_hex())
             }
+            Chain::LiquidV1 => write!(f, "liquidv1"),
+            Chain::LiquidTestnet => write!(f, "liquidtestnet"),
+            Chain::Other(params) => match builtin_chain_name(params) {
+                Some(name) => f.write_str(name),
+                None => f.write_str(&params.to_bech32_string()),
+            },
         }
     }
 }
 
+/// Returns name of the built-in chain whose parameters are exactly the same as
+/// `params`. Chains sharing genesis with a built-in one but differing in any
+/// other parameter (like team signets) have no short name, since it will be
+/// parsed back into the built-in parameters.
+fn builtin_chain_name(params: &ChainParams) -> Option<&'static str> {
+    let builtin: [(&ChainParams, &'static str); 7] = [
+        (&CHAIN_PARAMS_MAINNET, "bitcoin"),
+        (&CHAIN_PARAMS_TESTNET, "testnet"),
+        (&CHAIN_PARAMS_TESTNET4, "testnet4"),
+        (&CHAIN_PARAMS_REGTEST, "regtest"),
+        (&CHAIN_PARAMS_SIGNET, "signet"),
+        (&CHAIN_PARAMS_LIQUIDV1, "liquidv1"),
+        (&CHAIN_PARAMS_LIQUID_TESTNET, "liquidtestnet"),
+    ];
+    let encoded = strict_serialize(params).ok()?;
+    builtin
+        .iter()
+        .find(|(builtin, _)| {
+            builtin.genesis_hash == params.genesis_hash
+                && strict_serialize(*builtin).ok().as_ref() == Some(&encoded)
+        })
+        .map(|(_, name)| *name)
+}
+
 /// Chain data parse errors
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Display, Error, From)]
 #[display(doc_comments)]
 pub enum ParseError {
     /// The provided string does not matches any known chain; chain parameters
-    /// can't be guessed. Please use `other:0x<hex_encoded_parameters>` for
-    /// all non-standard networks.
+    /// can't be guessed. Please use `chain1...` bech32 string or
+    /// `other:<hex_encoded_parameters>` for all non-standard networks.
     WrongNetworkName,
 
+    /// Chain parameters can't be decoded from `chain1...` bech32 string: it
+    /// has invalid checksum, compression or parameters encoding.
+    ChainParamsBech32,
+
     /// Chain parameters can't be decoded. Please check that they are provided
     /// as a hexadecimal string starting with `0x` sign (case is irrelevant).
     #[from(strict_encoding::Error)]
@@ -962,6 +1436,10 @@ pub enum ParseError {
     /// provided string contains
     #[from]
     GenesisHashEncoding(hex::Error),
+
+    /// Can't decode signet challenge script, please make sure that it is
+    /// provided as a hexadecimal string.
+    SignetChallengeEncoding,
 }
 
 impl FromStr for Chain {
@@ -975,12 +1453,19 @@ impl FromStr for Chain {
             {
                 Ok(Chain::Mainnet)
             }
-            // Here we do not use `tb` prefix, since it matches multiple options
+            // Here we do not use `tb` prefix, since it matches multiple
+            // options; use `Chain::from_address` to get all
+            // candidate chains
             s if s == CHAIN_PARAMS_TESTNET.name
                 || s == CHAIN_PARAMS_TESTNET.bip70_name =>
             {
                 Ok(Chain::Testnet3)
             }
+            s if s == CHAIN_PARAMS_TESTNET4.name
+                || s == CHAIN_PARAMS_TESTNET4.bip70_name =>
+            {
+                Ok(Chain::Testnet4)
+            }
             s if s == CHAIN_PARAMS_REGTEST.name
                 || s == CHAIN_PARAMS_REGTEST.bip70_name =>
             {
@@ -996,6 +1481,22 @@ impl FromStr for Chain {
             {
                 Ok(Chain::LiquidV1)
             }
+            s if s == CHAIN_PARAMS_LIQUID_TESTNET.name
+                || s == CHAIN_PARAMS_LIQUID_TESTNET.bip70_name =>
+            {
+                Ok(Chain::LiquidTestnet)
+            }
+            s if s.starts_with(HRP_CHAIN)
+                && s[HRP_CHAIN.len()..].starts_with('1') =>
+            {
+                Ok(Chain::Other(Box::new(
+                    ChainParams::from_bech32_str(&s)
+                        .map_err(|_| ParseError::ChainParamsBech32)?,
+                )))
+            }
+            s if !s.contains(':') => ChainRegistry::get(&s)
+                .map(|params| Chain::Other(Box::new(params)))
+                .ok_or(ParseError::WrongNetworkName),
             s => {
                 let mut parts = s.split(':');
                 let prefix =
@@ -1009,6 +1510,10 @@ impl FromStr for Chain {
                     "signet" => {
                         Ok(Chain::SignetCustom(BlockHash::from_hex(data)?))
                     }
+                    "signetchallenge" => Ok(Chain::signet_with_challenge(
+                        Script::from_hex(data)
+                            .map_err(|_| ParseError::SignetChallengeEncoding)?,
+                    )),
                     "other" => Ok(Chain::Other(Box::new(strict_deserialize(
                         &Vec::from_hex(data)
                             .map_err(|_| ParseError::ChainParamsEncoding)?,
@@ -1021,7 +1526,9 @@ impl FromStr for Chain {
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrow, clippy::needless_borrows_for_generic_args)]
 mod test {
+    use strict_encoding::strict_serialize;
     use strict_encoding_test::{
         test_encoding_enum, test_encoding_enum_by_values,
         test_encoding_enum_u8_exhaustive, test_encoding_roundtrip,
@@ -1114,6 +1621,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_p2p_network_id_encoding() {
+        let networks = [
+            P2pNetworkId::Mainnet,
+            P2pNetworkId::Testnet,
+            P2pNetworkId::Regtest,
+            P2pNetworkId::Signet,
+            P2pNetworkId::Other(0x0102030),
+            P2pNetworkId::Testnet4,
+            P2pNetworkId::LiquidV1,
+            P2pNetworkId::LiquidTestnet,
+        ];
+        // Variants added later do not change order of the original ones
+        assert!(networks.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for network in networks {
+            let data = strict_serialize(&network).unwrap();
+            assert_eq!(data, network.as_magic().to_le_bytes());
+            let decoded: P2pNetworkId = strict_deserialize(&data).unwrap();
+            if network == P2pNetworkId::LiquidV1 {
+                // Liquid V1 shares magic number with regtest
+                assert_eq!(decoded, P2pNetworkId::Regtest);
+            } else {
+                assert_eq!(decoded, network);
+            }
+        }
+        let params: ChainParams = strict_deserialize(
+            strict_serialize(&*CHAIN_PARAMS_LIQUIDV1).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(params.p2p_magic, P2pNetworkId::LiquidV1);
+    }
+
     #[test]
     fn test_p2p_magic_number_from() {
         assert_eq!(
@@ -1136,6 +1676,22 @@ mod test {
             P2pNetworkId::from(0x0102030),
             P2pNetworkId::Other(0x0102030)
         );
+        assert_eq!(P2pNetworkId::from(0x62dd0e41), P2pNetworkId::LiquidTestnet);
+        assert_eq!(
+            P2pNetworkId::from(P2pNetworkId::LiquidV1.as_magic()),
+            P2pNetworkId::Regtest
+        );
+        assert_eq!(
+            P2pNetworkId::all_from_magic(P2pNetworkId::LiquidV1.as_magic()),
+            vec![P2pNetworkId::Regtest, P2pNetworkId::LiquidV1]
+        );
+        assert_eq!(P2pNetworkId::all_from_magic(0x0102030), vec![
+            P2pNetworkId::Other(0x0102030)
+        ]);
+        assert_eq!(
+            Chain::LiquidV1.chain_params().p2p_magic,
+            P2pNetworkId::LiquidV1
+        );
 
         assert_eq!(
             P2pNetworkId::Mainnet.as_magic(),
@@ -1325,7 +1881,186 @@ mod test {
 
     #[test]
     fn test_chain_encode() {
-        const DATA_CHAINPARAMS_MAINNET: [u8; 142] = [
+        const DATA_CHAINPARAMS_MAINNET: [u8; 495] = [
+            0xff, 0x43, 0x48, 0x4e, 0x1, 0x6f, 0xe2, 0x8c, 0xa, 0xb6, 0xf1,
+            0xb3, 0x72, 0xc1, 0xa6, 0xa2, 0x46, 0xae, 0x63, 0xf7, 0x4f, 0x93,
+            0x1e, 0x83, 0x65, 0xe1, 0x5a, 0x8, 0x9c, 0x68, 0xd6, 0x19, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x7, 0x0, 0x62, 0x69, 0x74, 0x63, 0x6f, 0x69,
+            0x6e, 0xf9, 0xbe, 0xb4, 0xd9, 0x4, 0x0, 0x6d, 0x61, 0x69, 0x6e,
+            0x2, 0x0, 0x62, 0x63, 0x0, 0x5, 0x0, 0x8d, 0x20, 0x8c, 0x20, 0x8,
+            0x0, 0x0, 0x5b, 0x7a, 0x3, 0x0, 0x1, 0x1d, 0xed, 0x5, 0x0, 0x2,
+            0xcd, 0x8c, 0x5, 0x0, 0x3, 0x0, 0x66, 0x6, 0x0, 0x4, 0x20, 0x5a,
+            0x7, 0x0, 0x5, 0x0, 0xd4, 0xa, 0x0, 0x6, 0xb4, 0xb2, 0x7, 0x0, 0x7,
+            0x10, 0xeb, 0x9, 0x0, 0x0, 0x22, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x3, 0x0, 0x42, 0x54, 0x43, 0x7, 0x0, 0x42, 0x69, 0x74, 0x63, 0x6f,
+            0x69, 0x6e, 0x7, 0x0, 0x73, 0x61, 0x74, 0x6f, 0x73, 0x68, 0x69,
+            0x0, 0xe1, 0xf5, 0x5, 0x0, 0x0, 0x0, 0x0, 0x6f, 0xe2, 0x8c, 0xa,
+            0xb6, 0xf1, 0xb3, 0x72, 0xc1, 0xa6, 0xa2, 0x46, 0xae, 0x63, 0xf7,
+            0x4f, 0x93, 0x1e, 0x83, 0x65, 0xe1, 0x5a, 0x8, 0x9c, 0x68, 0xd6,
+            0x19, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x1e, 0xb2, 0x88,
+            0x4, 0xe4, 0xad, 0x88, 0x4, 0xb2, 0x7c, 0x9d, 0x4, 0x78, 0x78,
+            0x9d, 0x4, 0x46, 0x47, 0xb2, 0x4, 0xc, 0x43, 0xb2, 0x4, 0x3f, 0xb4,
+            0x95, 0x2, 0x5, 0xb0, 0x95, 0x2, 0xd3, 0x7e, 0xaa, 0x2, 0x99, 0x7a,
+            0xaa, 0x2, 0x29, 0xab, 0x5f, 0x49, 0x0, 0xf2, 0x5, 0x2a, 0x1, 0x0,
+            0x0, 0x0, 0x50, 0x34, 0x3, 0x0, 0x58, 0x2, 0x0, 0x0, 0xe0, 0x7,
+            0x0, 0x0, 0xff, 0xff, 0x0, 0x1d, 0x3, 0x0, 0x1, 0x0, 0x14, 0x0,
+            0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xb8, 0xb, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x80, 0x1a, 0x6, 0x0, 0x3, 0x0, 0xc, 0x0, 0x51,
+            0xc3, 0x52, 0xc3, 0xb8, 0xb, 0x7, 0x26, 0xac, 0x6e, 0xad, 0x6e,
+            0x5, 0x0, 0xc5, 0x0, 0x8, 0x0, 0x14, 0x0, 0x73, 0x65, 0x65, 0x64,
+            0x2e, 0x62, 0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0x2e, 0x73, 0x69,
+            0x70, 0x61, 0x2e, 0x62, 0x65, 0x13, 0x0, 0x64, 0x6e, 0x73, 0x73,
+            0x65, 0x65, 0x64, 0x2e, 0x62, 0x6c, 0x75, 0x65, 0x6d, 0x61, 0x74,
+            0x74, 0x2e, 0x6d, 0x65, 0x1d, 0x0, 0x73, 0x65, 0x65, 0x64, 0x2e,
+            0x62, 0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0x2e, 0x6a, 0x6f, 0x6e,
+            0x61, 0x73, 0x73, 0x63, 0x68, 0x6e, 0x65, 0x6c, 0x6c, 0x69, 0x2e,
+            0x63, 0x68, 0x16, 0x0, 0x73, 0x65, 0x65, 0x64, 0x2e, 0x62, 0x74,
+            0x63, 0x2e, 0x70, 0x65, 0x74, 0x65, 0x72, 0x74, 0x6f, 0x64, 0x64,
+            0x2e, 0x6e, 0x65, 0x74, 0x19, 0x0, 0x73, 0x65, 0x65, 0x64, 0x2e,
+            0x62, 0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0x2e, 0x73, 0x70, 0x72,
+            0x6f, 0x76, 0x6f, 0x6f, 0x73, 0x74, 0x2e, 0x6e, 0x6c, 0xf, 0x0,
+            0x64, 0x6e, 0x73, 0x73, 0x65, 0x65, 0x64, 0x2e, 0x65, 0x6d, 0x7a,
+            0x79, 0x2e, 0x64, 0x65, 0x14, 0x0, 0x73, 0x65, 0x65, 0x64, 0x2e,
+            0x62, 0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0x2e, 0x77, 0x69, 0x7a,
+            0x2e, 0x62, 0x69, 0x7a, 0x1b, 0x0, 0x73, 0x65, 0x65, 0x64, 0x2e,
+            0x6d, 0x61, 0x69, 0x6e, 0x6e, 0x65, 0x74, 0x2e, 0x61, 0x63, 0x68,
+            0x6f, 0x77, 0x6e, 0x6f, 0x64, 0x65, 0x73, 0x2e, 0x78, 0x79, 0x7a,
+            0x0, 0x0,
+        ];
+        const DATA_CHAINPARAMS_TESTNET: [u8; 467] = [
+            0xff, 0x43, 0x48, 0x4e, 0x1, 0x43, 0x49, 0x7f, 0xd7, 0xf8, 0x26,
+            0x95, 0x71, 0x8, 0xf4, 0xa3, 0xf, 0xd9, 0xce, 0xc3, 0xae, 0xba,
+            0x79, 0x97, 0x20, 0x84, 0xe9, 0xe, 0xad, 0x1, 0xea, 0x33, 0x9, 0x0,
+            0x0, 0x0, 0x0, 0x7, 0x0, 0x74, 0x65, 0x73, 0x74, 0x6e, 0x65, 0x74,
+            0xb, 0x11, 0x9, 0x7, 0x4, 0x0, 0x74, 0x65, 0x73, 0x74, 0x2, 0x0,
+            0x74, 0x62, 0x6f, 0xc4, 0x0, 0x9d, 0x47, 0x9c, 0x47, 0x8, 0x0, 0x0,
+            0x77, 0x52, 0x0, 0x0, 0x1, 0xfd, 0xe0, 0x8, 0x0, 0x2, 0x18, 0xc,
+            0x5, 0x0, 0x3, 0x40, 0xc0, 0xb, 0x0, 0x4, 0x40, 0xbc, 0xc, 0x0,
+            0x5, 0x40, 0xb3, 0x1e, 0x0, 0x6, 0x1, 0x0, 0x0, 0x0, 0x7, 0xec,
+            0x1, 0x1c, 0x0, 0x0, 0x22, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x4,
+            0x0, 0x74, 0x42, 0x54, 0x43, 0xc, 0x0, 0x54, 0x65, 0x73, 0x74,
+            0x20, 0x42, 0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0xc, 0x0, 0x54,
+            0x65, 0x73, 0x74, 0x20, 0x73, 0x61, 0x74, 0x6f, 0x73, 0x68, 0x69,
+            0x0, 0xe1, 0xf5, 0x5, 0x0, 0x0, 0x0, 0x0, 0x43, 0x49, 0x7f, 0xd7,
+            0xf8, 0x26, 0x95, 0x71, 0x8, 0xf4, 0xa3, 0xf, 0xd9, 0xce, 0xc3,
+            0xae, 0xba, 0x79, 0x97, 0x20, 0x84, 0xe9, 0xe, 0xad, 0x1, 0xea,
+            0x33, 0x9, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x1, 0xcf, 0x87, 0x35,
+            0x4, 0x94, 0x83, 0x35, 0x4, 0x62, 0x52, 0x4a, 0x4, 0x28, 0x4e,
+            0x4a, 0x4, 0xf6, 0x1c, 0x5f, 0x4, 0xbc, 0x18, 0x5f, 0x4, 0xef,
+            0x89, 0x42, 0x2, 0xb5, 0x85, 0x42, 0x2, 0x83, 0x54, 0x57, 0x2,
+            0x48, 0x50, 0x57, 0x2, 0xda, 0xe5, 0x49, 0x4d, 0x0, 0xf2, 0x5,
+            0x2a, 0x1, 0x0, 0x0, 0x0, 0x50, 0x34, 0x3, 0x0, 0x58, 0x2, 0x0,
+            0x0, 0xe0, 0x7, 0x0, 0x0, 0xff, 0xff, 0x0, 0x1d, 0x3, 0x0, 0x1,
+            0x0, 0x14, 0x0, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xb8, 0xb,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x80, 0x1a, 0x6, 0x0, 0x3, 0x0, 0xc,
+            0x0, 0x61, 0xea, 0x62, 0xea, 0xb9, 0xb, 0x17, 0x4d, 0xac, 0x6e,
+            0xad, 0x6e, 0x5, 0x0, 0x9e, 0x0, 0x5, 0x0, 0x25, 0x0, 0x74, 0x65,
+            0x73, 0x74, 0x6e, 0x65, 0x74, 0x2d, 0x73, 0x65, 0x65, 0x64, 0x2e,
+            0x62, 0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0x2e, 0x6a, 0x6f, 0x6e,
+            0x61, 0x73, 0x73, 0x63, 0x68, 0x6e, 0x65, 0x6c, 0x6c, 0x69, 0x2e,
+            0x63, 0x68, 0x17, 0x0, 0x73, 0x65, 0x65, 0x64, 0x2e, 0x74, 0x62,
+            0x74, 0x63, 0x2e, 0x70, 0x65, 0x74, 0x65, 0x72, 0x74, 0x6f, 0x64,
+            0x64, 0x2e, 0x6e, 0x65, 0x74, 0x21, 0x0, 0x73, 0x65, 0x65, 0x64,
+            0x2e, 0x74, 0x65, 0x73, 0x74, 0x6e, 0x65, 0x74, 0x2e, 0x62, 0x69,
+            0x74, 0x63, 0x6f, 0x69, 0x6e, 0x2e, 0x73, 0x70, 0x72, 0x6f, 0x76,
+            0x6f, 0x6f, 0x73, 0x74, 0x2e, 0x6e, 0x6c, 0x18, 0x0, 0x74, 0x65,
+            0x73, 0x74, 0x6e, 0x65, 0x74, 0x2d, 0x73, 0x65, 0x65, 0x64, 0x2e,
+            0x62, 0x6c, 0x75, 0x65, 0x6d, 0x61, 0x74, 0x74, 0x2e, 0x6d, 0x65,
+            0x1b, 0x0, 0x73, 0x65, 0x65, 0x64, 0x2e, 0x74, 0x65, 0x73, 0x74,
+            0x6e, 0x65, 0x74, 0x2e, 0x61, 0x63, 0x68, 0x6f, 0x77, 0x6e, 0x6f,
+            0x64, 0x65, 0x73, 0x2e, 0x78, 0x79, 0x7a, 0x0, 0x0,
+        ];
+        const DATA_CHAINPARAMS_SIGNET: [u8; 457] = [
+            0xff, 0x43, 0x48, 0x4e, 0x1, 0xf6, 0x1e, 0xee, 0x3b, 0x63, 0xa3,
+            0x80, 0xa4, 0x77, 0xa0, 0x63, 0xaf, 0x32, 0xb2, 0xbb, 0xc9, 0x7c,
+            0x9f, 0xf9, 0xf0, 0x1f, 0x2c, 0x42, 0x25, 0xe9, 0x73, 0x98, 0x81,
+            0x8, 0x0, 0x0, 0x0, 0x6, 0x0, 0x73, 0x69, 0x67, 0x6e, 0x65, 0x74,
+            0xa, 0x3, 0xcf, 0x40, 0x6, 0x0, 0x73, 0x69, 0x67, 0x6e, 0x65, 0x74,
+            0x2, 0x0, 0x74, 0x62, 0x6f, 0xc4, 0x0, 0xbd, 0x95, 0xbc, 0x95, 0x8,
+            0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x1, 0x1, 0x0, 0x0, 0x0, 0x2, 0x1,
+            0x0, 0x0, 0x0, 0x3, 0x1, 0x0, 0x0, 0x0, 0x4, 0x1, 0x0, 0x0, 0x0,
+            0x5, 0x0, 0x0, 0x0, 0x0, 0x6, 0x1, 0x0, 0x0, 0x0, 0x7, 0x1, 0x0,
+            0x0, 0x0, 0x0, 0x22, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x4, 0x0,
+            0x73, 0x42, 0x54, 0x43, 0xe, 0x0, 0x53, 0x69, 0x67, 0x6e, 0x65,
+            0x74, 0x20, 0x42, 0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0xe, 0x0,
+            0x53, 0x69, 0x67, 0x6e, 0x65, 0x74, 0x20, 0x73, 0x61, 0x74, 0x6f,
+            0x73, 0x68, 0x69, 0x0, 0xe1, 0xf5, 0x5, 0x0, 0x0, 0x0, 0x0, 0xf6,
+            0x1e, 0xee, 0x3b, 0x63, 0xa3, 0x80, 0xa4, 0x77, 0xa0, 0x63, 0xaf,
+            0x32, 0xb2, 0xbb, 0xc9, 0x7c, 0x9f, 0xf9, 0xf0, 0x1f, 0x2c, 0x42,
+            0x25, 0xe9, 0x73, 0x98, 0x81, 0x8, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0,
+            0xcf, 0x87, 0x35, 0x4, 0x94, 0x83, 0x35, 0x4, 0x62, 0x52, 0x4a,
+            0x4, 0x28, 0x4e, 0x4a, 0x4, 0xf6, 0x1c, 0x5f, 0x4, 0xbc, 0x18,
+            0x5f, 0x4, 0xef, 0x89, 0x42, 0x2, 0xb5, 0x85, 0x42, 0x2, 0x83,
+            0x54, 0x57, 0x2, 0x48, 0x50, 0x57, 0x2, 0x0, 0x8f, 0x4d, 0x5f, 0x0,
+            0xf2, 0x5, 0x2a, 0x1, 0x0, 0x0, 0x0, 0x50, 0x34, 0x3, 0x0, 0x58,
+            0x2, 0x0, 0x0, 0xe0, 0x7, 0x0, 0x0, 0xae, 0x77, 0x3, 0x1e, 0x4,
+            0x0, 0x1, 0x0, 0x14, 0x0, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0xb8, 0xb, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x80, 0x1a, 0x6, 0x0, 0x2,
+            0x0, 0x49, 0x0, 0x47, 0x0, 0x51, 0x21, 0x3, 0xad, 0x5e, 0xe, 0xda,
+            0xd1, 0x8c, 0xb1, 0xf0, 0xfc, 0xd, 0x28, 0xa3, 0xd4, 0xf1, 0xf3,
+            0xe4, 0x45, 0x64, 0x3, 0x37, 0x48, 0x9a, 0xbb, 0x10, 0x40, 0x4f,
+            0x2d, 0x1e, 0x8, 0x6b, 0xe4, 0x30, 0x21, 0x3, 0x59, 0xef, 0x50,
+            0x21, 0x96, 0x4f, 0xe2, 0x2d, 0x6f, 0x8e, 0x5, 0xb2, 0x46, 0x3c,
+            0x95, 0x40, 0xce, 0x96, 0x88, 0x3f, 0xe3, 0xb2, 0x78, 0x76, 0xf,
+            0x4, 0x8f, 0x51, 0x89, 0xf2, 0xe6, 0xc4, 0x52, 0xae, 0x3, 0x0, 0xc,
+            0x0, 0xb9, 0xec, 0xba, 0xec, 0xbb, 0xb, 0x37, 0x9b, 0xac, 0x6e,
+            0xad, 0x6e, 0x5, 0x0, 0x42, 0x0, 0x2, 0x0, 0x20, 0x0, 0x73, 0x65,
+            0x65, 0x64, 0x2e, 0x73, 0x69, 0x67, 0x6e, 0x65, 0x74, 0x2e, 0x62,
+            0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0x2e, 0x73, 0x70, 0x72, 0x6f,
+            0x76, 0x6f, 0x6f, 0x73, 0x74, 0x2e, 0x6e, 0x6c, 0x1a, 0x0, 0x73,
+            0x65, 0x65, 0x64, 0x2e, 0x73, 0x69, 0x67, 0x6e, 0x65, 0x74, 0x2e,
+            0x61, 0x63, 0x68, 0x6f, 0x77, 0x6e, 0x6f, 0x64, 0x65, 0x73, 0x2e,
+            0x78, 0x79, 0x7a, 0x0, 0x0,
+        ];
+        const DATA_CHAINPARAMS_LIQUID: [u8; 360] = [
+            0xff, 0x43, 0x48, 0x4e, 0x1, 0x14, 0x66, 0x27, 0x58, 0x36, 0x22,
+            0xd, 0xb2, 0x94, 0x4c, 0xa0, 0x59, 0xa3, 0xa1, 0xe, 0xf6, 0xfd,
+            0x2e, 0xa6, 0x84, 0xb0, 0x68, 0x8d, 0x2c, 0x37, 0x92, 0x96, 0x88,
+            0x8a, 0x20, 0x60, 0x3, 0x8, 0x0, 0x6c, 0x69, 0x71, 0x75, 0x69,
+            0x64, 0x76, 0x31, 0xfa, 0xbf, 0xb5, 0xda, 0x8, 0x0, 0x6c, 0x69,
+            0x71, 0x75, 0x69, 0x64, 0x76, 0x31, 0x2, 0x0, 0x65, 0x78, 0x39,
+            0x27, 0x1, 0xc, 0x82, 0x1b, 0x81, 0x1b, 0x8, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x3,
+            0x0, 0x0, 0x0, 0x0, 0x4, 0x0, 0x0, 0x0, 0x0, 0x5, 0xff, 0xff, 0xff,
+            0xff, 0x6, 0x1, 0x0, 0x0, 0x0, 0x7, 0x40, 0x42, 0xf, 0x0, 0x1,
+            0x22, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x4, 0x0, 0x4c, 0x42,
+            0x54, 0x43, 0xe, 0x0, 0x4c, 0x69, 0x71, 0x75, 0x69, 0x64, 0x20,
+            0x42, 0x69, 0x74, 0x63, 0x6f, 0x69, 0x6e, 0xe, 0x0, 0x4c, 0x69,
+            0x71, 0x75, 0x69, 0x64, 0x20, 0x73, 0x61, 0x74, 0x6f, 0x73, 0x68,
+            0x69, 0x0, 0xe1, 0xf5, 0x5, 0x0, 0x0, 0x0, 0x0, 0x14, 0x66, 0x27,
+            0x58, 0x36, 0x22, 0xd, 0xb2, 0x94, 0x4c, 0xa0, 0x59, 0xa3, 0xa1,
+            0xe, 0xf6, 0xfd, 0x2e, 0xa6, 0x84, 0xb0, 0x68, 0x8d, 0x2c, 0x37,
+            0x92, 0x96, 0x88, 0x8a, 0x20, 0x60, 0x3, 0x0, 0x0, 0x0, 0x1e, 0xb2,
+            0x88, 0x4, 0xe4, 0xad, 0x88, 0x4, 0xb2, 0x7c, 0x9d, 0x4, 0x78,
+            0x78, 0x9d, 0x4, 0x46, 0x47, 0xb2, 0x4, 0xc, 0x43, 0xb2, 0x4, 0x3f,
+            0xb4, 0x95, 0x2, 0x5, 0xb0, 0x95, 0x2, 0xd3, 0x7e, 0xaa, 0x2, 0x99,
+            0x7a, 0xaa, 0x2, 0xda, 0xe5, 0x49, 0x4d, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3c, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x29, 0x0, 0x66,
+            0x0, 0x0, 0x0, 0x0, 0x6d, 0x52, 0x1c, 0x38, 0xec, 0x1e, 0xa1, 0x57,
+            0x34, 0xae, 0x22, 0xb7, 0xc4, 0x60, 0x64, 0x41, 0x28, 0x29, 0xc0,
+            0xd0, 0x57, 0x9f, 0xa, 0x71, 0x3d, 0x1c, 0x4, 0xed, 0xe9, 0x79,
+            0x2, 0x6f, 0x2, 0x0, 0x6c, 0x71, 0x1, 0x0, 0x14, 0x0, 0x64, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xb8, 0xb, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x80, 0x1a, 0x6, 0x0, 0x3, 0x0, 0xc, 0x0, 0x38, 0xc7, 0x39,
+            0xc7, 0xb8, 0xb, 0x7, 0x26, 0xac, 0x6e, 0xad, 0x6e,
+        ];
+
+        test_encoding_roundtrip(&Chain::Mainnet, DATA_CHAINPARAMS_MAINNET)
+            .unwrap();
+        test_encoding_roundtrip(&Chain::Testnet3, DATA_CHAINPARAMS_TESTNET)
+            .unwrap();
+        test_encoding_roundtrip(&Chain::Signet, DATA_CHAINPARAMS_SIGNET)
+            .unwrap();
+        test_encoding_roundtrip(&Chain::LiquidV1, DATA_CHAINPARAMS_LIQUID)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_chain_decode_legacy() {
+        // Chain parameters encoded with unversioned layout, preceding
+        // introduction of the encoding version and extension records
+        const DATA_CHAINPARAMS_MAINNET_LEGACY: [u8; 142] = [
             0x6f, 0xe2, 0x8c, 0xa, 0xb6, 0xf1, 0xb3, 0x72, 0xc1, 0xa6, 0xa2,
             0x46, 0xae, 0x63, 0xf7, 0x4f, 0x93, 0x1e, 0x83, 0x65, 0xe1, 0x5a,
             0x8, 0x9c, 0x68, 0xd6, 0x19, 0x0, 0x0, 0x0, 0x0, 0x0, 0x7, 0x0,
@@ -1340,7 +2075,7 @@ mod test {
             0xe1, 0x5a, 0x8, 0x9c, 0x68, 0xd6, 0x19, 0x0, 0x0, 0x0, 0x0, 0x0,
             0x0, 0x0, 0x1,
         ];
-        const DATA_CHAINPARAMS_TESTNET: [u8; 153] = [
+        const DATA_CHAINPARAMS_TESTNET_LEGACY: [u8; 153] = [
             0x43, 0x49, 0x7f, 0xd7, 0xf8, 0x26, 0x95, 0x71, 0x8, 0xf4, 0xa3,
             0xf, 0xd9, 0xce, 0xc3, 0xae, 0xba, 0x79, 0x97, 0x20, 0x84, 0xe9,
             0xe, 0xad, 0x1, 0xea, 0x33, 0x9, 0x0, 0x0, 0x0, 0x0, 0x7, 0x0,
@@ -1356,7 +2091,7 @@ mod test {
             0x20, 0x84, 0xe9, 0xe, 0xad, 0x1, 0xea, 0x33, 0x9, 0x0, 0x0, 0x0,
             0x0, 0x0, 0x1, 0x1,
         ];
-        const DATA_CHAINPARAMS_SIGNET: [u8; 158] = [
+        const DATA_CHAINPARAMS_SIGNET_LEGACY: [u8; 158] = [
             0xf6, 0x1e, 0xee, 0x3b, 0x63, 0xa3, 0x80, 0xa4, 0x77, 0xa0, 0x63,
             0xaf, 0x32, 0xb2, 0xbb, 0xc9, 0x7c, 0x9f, 0xf9, 0xf0, 0x1f, 0x2c,
             0x42, 0x25, 0xe9, 0x73, 0x98, 0x81, 0x8, 0x0, 0x0, 0x0, 0x6, 0x0,
@@ -1372,7 +2107,7 @@ mod test {
             0xbb, 0xc9, 0x7c, 0x9f, 0xf9, 0xf0, 0x1f, 0x2c, 0x42, 0x25, 0xe9,
             0x73, 0x98, 0x81, 0x8, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0,
         ];
-        const DATA_CHAINPARAMS_LIQUID: [u8; 162] = [
+        const DATA_CHAINPARAMS_LIQUID_LEGACY: [u8; 162] = [
             0x14, 0x66, 0x27, 0x58, 0x36, 0x22, 0xd, 0xb2, 0x94, 0x4c, 0xa0,
             0x59, 0xa3, 0xa1, 0xe, 0xf6, 0xfd, 0x2e, 0xa6, 0x84, 0xb0, 0x68,
             0x8d, 0x2c, 0x37, 0x92, 0x96, 0x88, 0x8a, 0x20, 0x60, 0x3, 0x8,
@@ -1390,14 +2125,49 @@ mod test {
             0x0, 0x0, 0x0,
         ];
 
-        test_encoding_roundtrip(&Chain::Mainnet, DATA_CHAINPARAMS_MAINNET)
-            .unwrap();
-        test_encoding_roundtrip(&Chain::Testnet3, DATA_CHAINPARAMS_TESTNET)
-            .unwrap();
-        test_encoding_roundtrip(&Chain::Signet, DATA_CHAINPARAMS_SIGNET)
-            .unwrap();
-        test_encoding_roundtrip(&Chain::LiquidV1, DATA_CHAINPARAMS_LIQUID)
-            .unwrap();
+        for (chain, data) in [
+            (Chain::Mainnet, &DATA_CHAINPARAMS_MAINNET_LEGACY[..]),
+            (Chain::Testnet3, &DATA_CHAINPARAMS_TESTNET_LEGACY[..]),
+            (Chain::Signet, &DATA_CHAINPARAMS_SIGNET_LEGACY[..]),
+            (Chain::LiquidV1, &DATA_CHAINPARAMS_LIQUID_LEGACY[..]),
+        ] {
+            let params = ChainParams::strict_decode(data).unwrap();
+            assert_eq!(
+                params.activation_height(Deployment::Lightning),
+                chain.activation_height(Deployment::Lightning)
+            );
+            assert_eq!(
+                params.activation_height(Deployment::Rgb),
+                chain.activation_height(Deployment::Rgb)
+            );
+            // Fields missing from the legacy layout are taken from the
+            // built-in chain; legacy Liquid parameters used bitcoin mainnet
+            // magic number
+            let mut expected = chain.chain_params();
+            if chain == Chain::LiquidV1 {
+                expected.p2p_magic = P2pNetworkId::Mainnet;
+            }
+            assert_eq!(
+                strict_serialize(&params).unwrap(),
+                strict_serialize(&expected).unwrap()
+            );
+            assert_eq!(Chain::strict_decode(data).unwrap(), chain);
+        }
+
+        // Unknown chains get default values for the missing fields
+        let mut data = DATA_CHAINPARAMS_TESTNET_LEGACY;
+        data[0] ^= 0xFF;
+        let params = ChainParams::strict_decode(&data[..]).unwrap();
+        assert_eq!(params.name, "testnet");
+        assert_eq!(params.address_prefixes, AddressPrefixes::testnet());
+        assert_eq!(params.bip32_versions, Bip32Versions::TESTNET);
+        assert_eq!(params.deployments.keys().collect::<Vec<_>>(), vec![
+            &Deployment::Lightning,
+            &Deployment::Rgb
+        ]);
+        assert_eq!(params.economics, ChainEconomics::default());
+        assert!(params.seeds.is_empty());
+        assert!(matches!(Chain::from(params), Chain::Regtest(_)));
     }
 
     #[test]
@@ -1538,6 +2308,41 @@ mod test {
             Chain::from(CHAIN_PARAMS_SIGNET.clone())
         );
         assert_eq!(Chain::LiquidV1, Chain::from(CHAIN_PARAMS_LIQUIDV1.clone()));
+        assert_eq!(Chain::Testnet4, Chain::from(CHAIN_PARAMS_TESTNET4.clone()));
+        assert_eq!(
+            Chain::LiquidTestnet,
+            Chain::from(CHAIN_PARAMS_LIQUID_TESTNET.clone())
+        );
+        for chain in Chain::all_standard() {
+            assert_eq!(
+                Chain::from_genesis_hash(chain.as_genesis_hash()).as_ref(),
+                Some(chain)
+            );
+            assert_eq!(&Chain::from_str(&chain.to_string()).unwrap(), chain);
+        }
+        assert_eq!(
+            Chain::Testnet4.as_genesis_hash().to_string(),
+            "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043"
+        );
+        assert_eq!(
+            Chain::LiquidTestnet.as_genesis_hash().to_string(),
+            "a771da8e52ee6ad581ed1e9a99825e5b3b7992225534eaa2ae23244fe26ab1c1"
+        );
+        assert_eq!(P2pNetworkId::Testnet4.as_magic().to_le_bytes(), [
+            0x1c, 0x16, 0x3f, 0x28
+        ]);
+        assert_eq!(
+            P2pNetworkId::from_magic(0x283f161c),
+            P2pNetworkId::Testnet4
+        );
+        assert_eq!(
+            bitcoin::Network::try_from(Chain::Testnet4).unwrap_err(),
+            ConversionImpossibleError
+        );
+        assert_eq!(
+            bitcoin::Network::try_from(Chain::LiquidTestnet).unwrap_err(),
+            ConversionImpossibleError
+        );
 
         assert_eq!(Chain::Mainnet, Chain::from(bitcoin::Network::Bitcoin));
         assert_eq!(Chain::Testnet3, Chain::from(bitcoin::Network::Testnet));
@@ -1587,6 +2392,91 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_chain_cmp_hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash(value: &impl std::hash::Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let random_hash = BlockHash::hash(b"rascafvsdg");
+        let mut chains = Chain::builtin();
+        chains.extend([
+            Chain::Regtest(random_hash),
+            Chain::SignetCustom(CHAIN_PARAMS_SIGNET.genesis_hash),
+            Chain::SignetCustom(random_hash),
+            Chain::SignetChallenge(Script::from_hex("51").unwrap()),
+            Chain::Other(Box::new(CHAIN_PARAMS_MAINNET.clone())),
+            Chain::Other(Box::new(CHAIN_PARAMS_SIGNET.clone())),
+        ]);
+        for a in &chains {
+            let params = a.chain_params();
+            assert_eq!(
+                a.as_signet_challenge(),
+                params.signet_challenge.as_ref()
+            );
+            assert_eq!(hash(a), hash(&params));
+            for b in &chains {
+                let other = b.chain_params();
+                assert_eq!(a == b, params == other);
+                assert_eq!(a.cmp(b), b.cmp(a).reverse());
+                if a == b {
+                    assert_eq!(hash(a), hash(b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chain_cmp_transitive() {
+        use std::collections::BTreeSet;
+
+        let mut no_challenge = CHAIN_PARAMS_SIGNET.clone();
+        no_challenge.signet_challenge = None;
+        let op_true = Script::from_hex("51").unwrap();
+        let mut op_true_params = CHAIN_PARAMS_SIGNET.clone();
+        op_true_params.signet_challenge = Some(op_true.clone());
+
+        let chains = [
+            Chain::Signet,
+            Chain::SignetChallenge(op_true),
+            Chain::SignetCustom(CHAIN_PARAMS_SIGNET.genesis_hash),
+            Chain::Regtest(CHAIN_PARAMS_SIGNET.genesis_hash),
+            Chain::Other(Box::new(no_challenge.clone())),
+            Chain::Other(Box::new(op_true_params)),
+            Chain::Mainnet,
+        ];
+
+        // Signet parameters without the challenge are the default signet
+        assert_eq!(Chain::Other(Box::new(no_challenge.clone())), Chain::Signet);
+        assert_ne!(
+            Chain::Other(Box::new(no_challenge)),
+            Chain::SignetChallenge(Script::from_hex("51").unwrap())
+        );
+
+        for a in &chains {
+            for b in &chains {
+                for c in &chains {
+                    if a == b && b == c {
+                        assert_eq!(a, c);
+                    }
+                    if a <= b && b <= c {
+                        assert!(a <= c);
+                    }
+                    if a == b {
+                        assert_eq!(a.cmp(c), b.cmp(c));
+                        assert_eq!(a.chain_params(), b.chain_params());
+                    }
+                }
+            }
+        }
+        let set = chains.iter().cloned().collect::<BTreeSet<_>>();
+        assert_eq!(set.len(), 3);
+    }
+
     #[test]
     fn test_chains_display() {
         let custom_hash = BlockHash::hash(b"00350429507202701943");
@@ -1594,6 +2484,8 @@ mod test {
         assert_eq!(format!("{}", Chain::Testnet3), "testnet");
         assert_eq!(format!("{}", Chain::Signet), "signet");
         assert_eq!(format!("{}", Chain::LiquidV1), "liquidv1");
+        assert_eq!(format!("{}", Chain::Testnet4), "testnet4");
+        assert_eq!(format!("{}", Chain::LiquidTestnet), "liquidtestnet");
         assert_eq!(
             format!("{}", Chain::Regtest(CHAIN_PARAMS_REGTEST.genesis_hash)),
             "regtest"
@@ -1674,4 +2566,70 @@ mod test {
             Chain::from(custom_params)
         );
     }
+
+    #[test]
+    fn test_chain_bech32() {
+        let mut params = CHAIN_PARAMS_REGTEST.clone();
+        params.name = s!("private");
+        params.genesis_hash = BlockHash::hash(b"00350429507202701943");
+        let chain = Chain::Other(Box::new(params.clone()));
+
+        let s = chain.to_string();
+        assert!(s.starts_with("chain1"));
+        let hex =
+            format!("other:{}", strict_serialize(&params).unwrap().to_hex());
+        assert!(s.len() < hex.len());
+
+        for s in [&s, &s.to_uppercase(), &hex] {
+            match Chain::from_str(s).unwrap() {
+                Chain::Other(decoded) => assert_eq!(
+                    strict_serialize(decoded.as_ref()).unwrap(),
+                    strict_serialize(&params).unwrap()
+                ),
+                other => panic!("unexpected chain {:?}", other),
+            }
+        }
+
+        let mut corrupted = s.clone();
+        let last = if corrupted.pop() == Some('q') {
+            'p'
+        } else {
+            'q'
+        };
+        corrupted.push(last);
+        assert_eq!(
+            Chain::from_str(&corrupted).unwrap_err(),
+            ParseError::ChainParamsBech32
+        );
+        assert_eq!(
+            Chain::from_str("chain1qqqqqqqqqqqqqqqq").unwrap_err(),
+            ParseError::ChainParamsBech32
+        );
+    }
+
+    #[test]
+    fn test_chain_bech32_builtin_genesis() {
+        // Team signet shares genesis with the default signet
+        let mut team_signet = CHAIN_PARAMS_SIGNET.clone();
+        team_signet.signet_challenge = Some(Script::from_hex("51").unwrap());
+        let mut renamed = CHAIN_PARAMS_MAINNET.clone();
+        renamed.name = s!("renamed");
+        let mut no_dns_seeds = CHAIN_PARAMS_TESTNET.clone();
+        no_dns_seeds.seeds = ChainSeeds::default();
+
+        for params in [team_signet, renamed, no_dns_seeds] {
+            let s = Chain::Other(Box::new(params.clone())).to_string();
+            assert!(s.starts_with("chain1"));
+            assert_eq!(
+                strict_serialize(&Chain::from_str(&s).unwrap().chain_params())
+                    .unwrap(),
+                strict_serialize(&params).unwrap()
+            );
+        }
+
+        for chain in Chain::all_standard() {
+            let other = Chain::Other(Box::new(chain.chain_params()));
+            assert_eq!(other.to_string(), chain.to_string());
+        }
+    }
 }
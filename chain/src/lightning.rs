@@ -0,0 +1,238 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Chain identification used by Lightning network protocol (BOLTs): chain
+//! hashes, BOLT11 invoice currency prefixes and BOLT12 offer chains.
+
+use bitcoin::hashes::{sha256d, Hash};
+use bitcoin::BlockHash;
+
+use crate::Chain;
+
+/// Human-readable part of BOLT12 offers
+pub const BOLT12_HRP_OFFER: &str = "lno";
+/// Human-readable part of BOLT12 invoice requests
+pub const BOLT12_HRP_INVOICE_REQUEST: &str = "lnr";
+/// Human-readable part of BOLT12 invoices
+pub const BOLT12_HRP_INVOICE: &str = "lni";
+
+hash_newtype!(
+    ChainHash,
+    sha256d::Hash,
+    32,
+    doc = "Chain identifier used by Lightning network protocol messages, \
+           matching genesis block hash in BOLT byte order (which is the \
+           internal byte order, displayed without reversing)",
+    false
+);
+
+impl strict_encoding::Strategy for ChainHash {
+    type Strategy = strict_encoding::strategies::HashFixedBytes;
+}
+
+impl From<BlockHash> for ChainHash {
+    fn from(block_hash: BlockHash) -> Self {
+        ChainHash::from_inner(block_hash.into_inner())
+    }
+}
+
+impl From<ChainHash> for BlockHash {
+    fn from(chain_hash: ChainHash) -> Self {
+        BlockHash::from_inner(chain_hash.into_inner())
+    }
+}
+
+impl Chain {
+    /// Returns Lightning network chain hash of the chain. Since all signets
+    /// share the same genesis block, they also share the chain hash.
+    #[inline]
+    pub fn chain_hash(&self) -> ChainHash { (*self.as_genesis_hash()).into() }
+
+    /// Detects chain from the Lightning network chain hash; see
+    /// [`Chain::from_genesis_hash`] for the details.
+    #[inline]
+    pub fn from_chain_hash(chain_hash: &ChainHash) -> Option<Chain> {
+        Chain::from_genesis_hash(&(*chain_hash).into())
+    }
+
+    /// Returns currency prefix used by BOLT11 invoices for the chain, which
+    /// matches BIP173 prefix for all chains except signets, using `tbs`
+    pub fn bolt11_prefix(&self) -> String {
+        match self {
+            Chain::Signet
+            | Chain::SignetCustom(_)
+            | Chain::SignetChallenge(_) => {
+                s!("tbs")
+            }
+            _ => self.chain_params().bip173_prefix,
+        }
+    }
+
+    /// Detects chain from BOLT11 invoice currency prefix, or from the full
+    /// human-readable part of the invoice (like `lnbc2500u`). Testnet3 and
+    /// testnet4 share `tb` prefix, which is resolved into testnet3; chains
+    /// registered with [`ChainRegistry`](crate::ChainRegistry) are matched
+    /// after the built-in ones.
+    pub fn from_bolt11_prefix(prefix: &str) -> Option<Chain> {
+        let prefix = prefix.to_ascii_lowercase();
+        // Currency prefix is followed by optional amount, which starts with
+        // a digit
+        let prefix = match prefix.strip_prefix("ln") {
+            Some(hrp) => hrp.split(|c: char| c.is_ascii_digit()).next()?,
+            None => &prefix,
+        };
+        Chain::known()
+            .into_iter()
+            .find(|chain| chain.bolt11_prefix() == prefix)
+    }
+
+    /// Returns list of chain hashes to be put into `offer_chains` field of
+    /// BOLT12 offers for this chain: offers for bitcoin mainnet omit the
+    /// field
+    pub fn bolt12_offer_chains(&self) -> Vec<ChainHash> {
+        match self {
+            Chain::Mainnet => vec![],
+            chain => vec![chain.chain_hash()],
+        }
+    }
+
+    /// Detects chains from `offer_chains` field of BOLT12 offer, which
+    /// defaults to bitcoin mainnet if the field is absent or empty. Returns
+    /// `None` if any of the chains is not known.
+    pub fn from_bolt12_offer_chains(
+        chains: &[ChainHash],
+    ) -> Option<Vec<Chain>> {
+        if chains.is_empty() {
+            return Some(vec![Chain::Mainnet]);
+        }
+        chains.iter().map(Chain::from_chain_hash).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{CHAIN_PARAMS_REGTEST, SIGNET_CHALLENGE_DEFAULT};
+
+    // Chain hashes from BOLT #0 and BOLT #12 test vectors
+    const MAINNET: &str =
+        "6fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000";
+    const TESTNET: &str =
+        "43497fd7f826957108f4a30fd9cec3aeba79972084e90ead01ea330900000000";
+    const REGTEST: &str =
+        "06226e46111a0b59caaf126043eb5bbf28c34f3a5e332a1fc7b2b73cf188910f";
+    const SIGNET: &str =
+        "f61eee3b63a380a477a063af32b2bbc97c9ff9f01f2c4225e973988108000000";
+
+    fn regtest() -> Chain { Chain::Regtest(CHAIN_PARAMS_REGTEST.genesis_hash) }
+
+    #[test]
+    fn chain_hash() {
+        for (chain, hex) in [
+            (Chain::Mainnet, MAINNET),
+            (Chain::Testnet3, TESTNET),
+            (regtest(), REGTEST),
+            (Chain::Signet, SIGNET),
+        ] {
+            let chain_hash = ChainHash::from_str(hex).unwrap();
+            assert_eq!(chain.chain_hash(), chain_hash);
+            assert_eq!(chain.chain_hash().to_string(), hex);
+            assert_eq!(Chain::from_chain_hash(&chain_hash), Some(chain));
+        }
+
+        for chain in Chain::all_standard() {
+            let chain_hash = chain.chain_hash();
+            assert_eq!(BlockHash::from(chain_hash), *chain.as_genesis_hash());
+            assert_eq!(
+                Chain::from_chain_hash(&chain_hash).as_ref(),
+                Some(chain)
+            );
+        }
+        assert_eq!(
+            Chain::SignetChallenge(bitcoin::Script::from(
+                SIGNET_CHALLENGE_DEFAULT.to_vec(),
+            ))
+            .chain_hash(),
+            Chain::Signet.chain_hash()
+        );
+        assert_eq!(Chain::from_chain_hash(&ChainHash::all_zeros()), None);
+    }
+
+    #[test]
+    fn bolt11() {
+        let _lock = crate::registry::test_lock();
+        // Invoice prefixes from BOLT #11 test vectors and implementations
+        for (hrp, chain) in [
+            ("lnbc", Chain::Mainnet),
+            ("lnbc2500u", Chain::Mainnet),
+            ("lnbc20m", Chain::Mainnet),
+            ("lnbc9678785340p", Chain::Mainnet),
+            ("LNBC1", Chain::Mainnet),
+            ("lntb20m", Chain::Testnet3),
+            ("lntbs10u", Chain::Signet),
+            ("lnbcrt1500n", regtest()),
+            ("lnex100n", Chain::LiquidV1),
+            ("lntex", Chain::LiquidTestnet),
+        ] {
+            assert_eq!(Chain::from_bolt11_prefix(hrp), Some(chain));
+        }
+        assert_eq!(Chain::from_bolt11_prefix("lnxx1"), None);
+        assert_eq!(Chain::from_bolt11_prefix("ln"), None);
+
+        for chain in Chain::all_standard().iter().cloned().chain([regtest()]) {
+            let prefix = chain.bolt11_prefix();
+            let expected = match chain {
+                Chain::Testnet4 => Chain::Testnet3,
+                ref chain => chain.clone(),
+            };
+            assert_eq!(Chain::from_bolt11_prefix(&prefix), Some(expected));
+        }
+        assert_eq!(Chain::Testnet4.bolt11_prefix(), "tb");
+        assert_eq!(Chain::Signet.bolt11_prefix(), "tbs");
+    }
+
+    #[test]
+    fn bolt12() {
+        assert_eq!(BOLT12_HRP_OFFER, "lno");
+        assert!(Chain::Mainnet.bolt12_offer_chains().is_empty());
+        assert_eq!(
+            Chain::from_bolt12_offer_chains(&[]),
+            Some(vec![Chain::Mainnet])
+        );
+
+        let chains = [
+            ChainHash::from_str(MAINNET).unwrap(),
+            ChainHash::from_str(TESTNET).unwrap(),
+        ];
+        assert_eq!(
+            Chain::from_bolt12_offer_chains(&chains),
+            Some(vec![Chain::Mainnet, Chain::Testnet3])
+        );
+        assert_eq!(
+            Chain::from_bolt12_offer_chains(&[
+                chains[0],
+                ChainHash::all_zeros()
+            ]),
+            None
+        );
+
+        for chain in Chain::all_standard().iter().skip(1) {
+            assert_eq!(
+                Chain::from_bolt12_offer_chains(&chain.bolt12_offer_chains()),
+                Some(vec![chain.clone()])
+            );
+        }
+    }
+}
@@ -0,0 +1,248 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Parsing of P2P message headers, allowing to detect chain and message type
+//! of the captured traffic without running a node. Elements-based chains use
+//! the same message framing as bitcoin.
+
+use bitcoin::hashes::{sha256d, Hash};
+
+use crate::{Chain, P2pMagicNumber, P2pNetworkId};
+
+/// Length of P2P message header: network magic number, command, payload
+/// length and payload checksum
+pub const P2P_HEADER_LEN: usize = 24;
+
+/// Length of the zero-padded command field of P2P message header
+const COMMAND_LEN: usize = 12;
+
+/// Errors parsing P2P message header
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum P2pHeaderError {
+    /// P2P message header requires {P2P_HEADER_LEN} bytes, while only {0}
+    /// bytes are provided
+    TooShort(usize),
+
+    /// P2P message command must consist of printable ASCII characters
+    /// padded with zeros
+    InvalidCommand,
+}
+
+/// Header of a P2P message
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct P2pHeader {
+    /// Network magic number
+    pub magic: P2pMagicNumber,
+
+    /// Message command (like `version` or `block`), without zero padding
+    pub command: String,
+
+    /// Length of the message payload
+    pub payload_len: u32,
+
+    /// First four bytes of the double SHA256 hash of the message payload
+    pub checksum: [u8; 4],
+}
+
+impl P2pHeader {
+    /// Parses P2P message header from the first [`P2P_HEADER_LEN`] bytes of
+    /// the data; the rest of the data (normally the message payload) is
+    /// ignored
+    pub fn parse(data: &[u8]) -> Result<P2pHeader, P2pHeaderError> {
+        if data.len() < P2P_HEADER_LEN {
+            return Err(P2pHeaderError::TooShort(data.len()));
+        }
+        let mut magic = [0u8; 4];
+        magic.copy_from_slice(&data[..4]);
+        let command = &data[4..4 + COMMAND_LEN];
+        let mut payload_len = [0u8; 4];
+        payload_len.copy_from_slice(&data[16..20]);
+        let mut checksum = [0u8; 4];
+        checksum.copy_from_slice(&data[20..24]);
+
+        // Same rules as used by Bitcoin Core: printable characters followed
+        // by zero padding only
+        let len = command.iter().position(|b| *b == 0).unwrap_or(COMMAND_LEN);
+        if !command[..len].iter().all(|b| (b' '..=b'~').contains(b))
+            || command[len..].iter().any(|b| *b != 0)
+        {
+            return Err(P2pHeaderError::InvalidCommand);
+        }
+
+        Ok(P2pHeader {
+            magic: P2pMagicNumber::from_le_bytes(magic),
+            command: String::from_utf8_lossy(&command[..len]).into_owned(),
+            payload_len: u32::from_le_bytes(payload_len),
+            checksum,
+        })
+    }
+
+    /// Returns network identified by the header magic number; see
+    /// [`P2pNetworkId::from_magic`] for the resolution of ambiguous magic
+    /// numbers. Since the header carries no other information about the
+    /// chain, Liquid V1 traffic is reported as [`P2pNetworkId::Regtest`];
+    /// use [`P2pHeader::networks`] or [`P2pHeader::chains`] to get all
+    /// candidates.
+    #[inline]
+    pub fn network(&self) -> P2pNetworkId {
+        P2pNetworkId::from_magic(self.magic)
+    }
+
+    /// Returns all known networks using the header magic number (like bitcoin
+    /// regtest and Liquid V1, which share the same magic number)
+    #[inline]
+    pub fn networks(&self) -> Vec<P2pNetworkId> {
+        P2pNetworkId::all_from_magic(self.magic)
+    }
+
+    /// Returns all known chains using the header magic number. Chains
+    /// registered with [`ChainRegistry`](crate::ChainRegistry) are reported
+    /// after the built-in ones as [`Chain::Other`]; empty list means
+    /// unknown network.
+    pub fn chains(&self) -> Vec<Chain> {
+        Chain::known()
+            .into_iter()
+            .filter(|chain| {
+                chain.chain_params().p2p_magic.as_magic() == self.magic
+            })
+            .collect()
+    }
+
+    /// Verifies that the payload matches the header length and checksum. Only
+    /// the first [`P2pHeader::payload_len`] bytes of the payload are used;
+    /// returns `None` if the payload is shorter (like for truncated captures)
+    /// and can't be verified.
+    pub fn verify_payload(&self, payload: &[u8]) -> Option<bool> {
+        let payload = payload.get(..self.payload_len as usize)?;
+        Some(sha256d::Hash::hash(payload)[..4] == self.checksum)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitcoin::consensus::encode::serialize;
+    use bitcoin::network::message::{NetworkMessage, RawNetworkMessage};
+
+    use super::*;
+    use crate::CHAIN_PARAMS_REGTEST;
+
+    fn message(magic: P2pMagicNumber, payload: NetworkMessage) -> Vec<u8> {
+        serialize(&RawNetworkMessage { magic, payload })
+    }
+
+    #[test]
+    fn verack() {
+        let _lock = crate::registry::test_lock();
+        // Mainnet `verack` message, which has empty payload
+        let data = [
+            0xf9, 0xbe, 0xb4, 0xd9, b'v', b'e', b'r', b'a', b'c', b'k', 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0x5d, 0xf6, 0xe0, 0xe2,
+        ];
+        let header = P2pHeader::parse(&data).unwrap();
+        assert_eq!(header, P2pHeader {
+            magic: 0xd9b4bef9,
+            command: s!("verack"),
+            payload_len: 0,
+            checksum: [0x5d, 0xf6, 0xe0, 0xe2],
+        });
+        assert_eq!(header.network(), P2pNetworkId::Mainnet);
+        assert_eq!(header.chains(), vec![Chain::Mainnet]);
+        assert_eq!(header.verify_payload(&[]), Some(true));
+    }
+
+    #[test]
+    fn chains() {
+        let _lock = crate::registry::test_lock();
+        let ping = NetworkMessage::Ping(0x0102030405060708);
+        for chain in Chain::all_standard() {
+            let magic = chain.chain_params().p2p_magic.as_magic();
+            let data = message(magic, ping.clone());
+            let header = P2pHeader::parse(&data).unwrap();
+            assert_eq!(header.command, "ping");
+            assert_eq!(header.payload_len, 8);
+            assert_eq!(
+                header.verify_payload(&data[P2P_HEADER_LEN..]),
+                Some(true)
+            );
+            assert!(header
+                .networks()
+                .contains(&chain.chain_params().p2p_magic));
+            assert!(header.chains().contains(chain));
+        }
+
+        // Bitcoin regtest and Liquid V1 share the same magic number
+        let data = message(P2pNetworkId::LiquidV1.as_magic(), ping.clone());
+        let header = P2pHeader::parse(&data).unwrap();
+        assert_eq!(header.network(), P2pNetworkId::Regtest);
+        assert_eq!(header.networks(), vec![
+            P2pNetworkId::Regtest,
+            P2pNetworkId::LiquidV1
+        ]);
+        assert_eq!(header.chains(), vec![
+            Chain::LiquidV1,
+            Chain::Regtest(CHAIN_PARAMS_REGTEST.genesis_hash)
+        ]);
+
+        let data = message(P2pNetworkId::Testnet4.as_magic(), ping);
+        let header = P2pHeader::parse(&data).unwrap();
+        assert_eq!(header.network(), P2pNetworkId::Testnet4);
+        assert_eq!(header.chains(), vec![Chain::Testnet4]);
+
+        let data = message(0x01020304, NetworkMessage::Verack);
+        let header = P2pHeader::parse(&data).unwrap();
+        assert_eq!(header.network(), P2pNetworkId::Other(0x01020304));
+        assert!(header.chains().is_empty());
+    }
+
+    #[test]
+    fn payload() {
+        let data =
+            message(P2pNetworkId::Testnet.as_magic(), NetworkMessage::Ping(1));
+        let header = P2pHeader::parse(&data).unwrap();
+        let payload = &data[P2P_HEADER_LEN..];
+        let mut tampered = data[P2P_HEADER_LEN..].to_vec();
+        tampered[0] ^= 0xff;
+        assert_eq!(header.verify_payload(&tampered), Some(false));
+        assert_eq!(header.verify_payload(&payload[..7]), None);
+        // Trailing data of the next message is ignored
+        assert_eq!(header.verify_payload(&data[P2P_HEADER_LEN..]), Some(true));
+        assert_eq!(
+            header.verify_payload(&[payload, &[0u8; 4][..]].concat()),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let data =
+            message(P2pNetworkId::Mainnet.as_magic(), NetworkMessage::Verack);
+        assert_eq!(
+            P2pHeader::parse(&data[..23]),
+            Err(P2pHeaderError::TooShort(23))
+        );
+
+        let mut invalid = data.clone();
+        invalid[10] = b'\n';
+        assert_eq!(
+            P2pHeader::parse(&invalid),
+            Err(P2pHeaderError::InvalidCommand)
+        );
+        let mut invalid = data;
+        invalid[12] = b'x';
+        assert_eq!(
+            P2pHeader::parse(&invalid),
+            Err(P2pHeaderError::InvalidCommand)
+        );
+    }
+}
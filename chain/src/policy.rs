@@ -0,0 +1,226 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Transaction relay policy of a chain: relay fees, dust thresholds and
+//! standardness limits applied by the nodes.
+
+use bitcoin::consensus::encode::VarInt;
+use bitcoin::Script;
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::{Chain, ChainParams};
+
+/// Maximal size of a script which may be spent; outputs with larger scripts
+/// are unspendable and are not subject to dust limits
+const MAX_SCRIPT_SIZE: usize = 10_000;
+
+/// Length of P2PKH output script
+const P2PKH_SCRIPT_LEN: usize = 25;
+
+/// Size of the input spending non-segwit output, as estimated by Bitcoin Core
+/// for dust threshold computation: outpoint, script length, signature and
+/// public key, and sequence number
+const SPEND_SIZE_LEGACY: u64 = 32 + 4 + 1 + 107 + 4;
+
+/// Virtual size of the input spending segwit output, as estimated by Bitcoin
+/// Core for dust threshold computation (with witness data discounted)
+const SPEND_SIZE_SEGWIT: u64 = 32 + 4 + 1 + (107 / 4) + 4;
+
+/// Policy rules applied by the nodes of a chain to the transactions they
+/// relay and accept into their mempools
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[derive(StrictEncode, StrictDecode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+pub struct RelayPolicy {
+    /// Minimal fee rate of the relayed transactions, in indivisible units of
+    /// the native asset per 1000 virtual bytes
+    pub min_relay_fee: u64,
+
+    /// Fee rate used to compute dust thresholds, in indivisible units of the
+    /// native asset per 1000 virtual bytes: an output is dust if spending it
+    /// costs more than its value
+    pub dust_relay_fee: u64,
+
+    /// Maximal weight of the standard transactions, in weight units
+    pub max_standard_tx_weight: u32,
+}
+
+impl Default for RelayPolicy {
+    fn default() -> Self { RelayPolicy::BITCOIN_CORE }
+}
+
+impl RelayPolicy {
+    /// Default relay policy of Bitcoin Core nodes
+    pub const BITCOIN_CORE: RelayPolicy = RelayPolicy {
+        min_relay_fee: 1000,
+        dust_relay_fee: 3000,
+        max_standard_tx_weight: 400_000,
+    };
+
+    /// Relay policy of Liquid sidechain nodes, which accept transactions
+    /// paying 0.1 sat/vB
+    pub const LIQUIDV1: RelayPolicy = RelayPolicy {
+        min_relay_fee: 100,
+        ..RelayPolicy::BITCOIN_CORE
+    };
+
+    /// Computes fee for a transaction of a given virtual size using fee rate
+    /// in units per 1000 virtual bytes, rounding it down like Bitcoin Core
+    /// does (but never to zero for non-zero sizes and rates)
+    pub fn fee_for(fee_rate: u64, vsize: u64) -> u64 {
+        let fee = (fee_rate as u128 * vsize as u128 / 1000) as u64;
+        if fee == 0 && fee_rate > 0 && vsize > 0 {
+            return 1;
+        }
+        fee
+    }
+
+    /// Returns minimal fee of a transaction of a given virtual size which
+    /// will be relayed by the nodes
+    #[inline]
+    pub fn min_relay_fee_for(&self, vsize: u64) -> u64 {
+        RelayPolicy::fee_for(self.min_relay_fee, vsize)
+    }
+
+    /// Returns dust threshold for an output with a script of a given length:
+    /// outputs with smaller value will not be relayed by the nodes
+    pub fn dust_limit_for(&self, script_len: usize, is_segwit: bool) -> u64 {
+        let output_size =
+            8 + VarInt(script_len as u64).len() as u64 + script_len as u64;
+        let spend_size = if is_segwit {
+            SPEND_SIZE_SEGWIT
+        } else {
+            SPEND_SIZE_LEGACY
+        };
+        RelayPolicy::fee_for(self.dust_relay_fee, output_size + spend_size)
+    }
+
+    /// Returns dust threshold for P2PKH outputs, which is used as the chain
+    /// dust limit ([`ChainParams::dust_limit`])
+    #[inline]
+    pub fn p2pkh_dust_limit(&self) -> u64 {
+        self.dust_limit_for(P2PKH_SCRIPT_LEN, false)
+    }
+
+    /// Returns dust threshold for an output with a given script. Provably
+    /// unspendable outputs (like `OP_RETURN`) have zero threshold.
+    pub fn dust_limit_for_script(&self, script: &Script) -> u64 {
+        if script.is_op_return() || script.len() > MAX_SCRIPT_SIZE {
+            return 0;
+        }
+        self.dust_limit_for(script.len(), script.is_witness_program())
+    }
+
+    /// Detects whether a transaction of a given weight is standard
+    #[inline]
+    pub fn is_standard_weight(&self, weight: u64) -> bool {
+        weight <= self.max_standard_tx_weight as u64
+    }
+}
+
+impl ChainParams {
+    /// Returns dust threshold for an output with a script of a given length
+    /// under the chain relay policy
+    #[inline]
+    pub fn dust_limit_for(&self, script_len: usize, is_segwit: bool) -> u64 {
+        self.relay_policy.dust_limit_for(script_len, is_segwit)
+    }
+}
+
+impl Chain {
+    /// Returns dust threshold for an output with a script of a given length
+    /// under the chain relay policy
+    #[inline]
+    pub fn dust_limit_for(&self, script_len: usize, is_segwit: bool) -> u64 {
+        self.chain_params().dust_limit_for(script_len, is_segwit)
+    }
+
+    /// Returns dust threshold for an output with a given script under the
+    /// chain relay policy
+    #[inline]
+    pub fn dust_limit_for_script(&self, script: &Script) -> u64 {
+        self.chain_params()
+            .relay_policy
+            .dust_limit_for_script(script)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitcoin::hashes::hex::FromHex;
+    use bitcoin::hashes::Hash;
+    use bitcoin::{PubkeyHash, WPubkeyHash, WScriptHash};
+
+    use super::*;
+
+    #[test]
+    fn dust_limits() {
+        let pkh = Script::new_p2pkh(&PubkeyHash::hash(b"pk"));
+        let wpkh = Script::new_v0_p2wpkh(&WPubkeyHash::hash(b"pk"));
+        let wsh = Script::new_v0_p2wsh(&WScriptHash::hash(b"script"));
+        let tr = Script::from_hex(&format!("5120{}", "11".repeat(32))).unwrap();
+        let op_return = Script::new_op_return(b"data");
+
+        // Values match Bitcoin Core defaults
+        let chain = Chain::Mainnet;
+        assert_eq!(chain.dust_limit_for(25, false), 546);
+        for chain in Chain::all_standard() {
+            let params = chain.chain_params();
+            assert_eq!(
+                params.dust_limit,
+                params.relay_policy.p2pkh_dust_limit()
+            );
+        }
+        assert_eq!(chain.dust_limit_for_script(&pkh), 546);
+        assert_eq!(chain.dust_limit_for_script(&wpkh), 294);
+        assert_eq!(chain.dust_limit_for_script(&wsh), 330);
+        assert_eq!(chain.dust_limit_for_script(&tr), 330);
+        assert_eq!(chain.dust_limit_for_script(&op_return), 0);
+        for script in [&pkh, &wpkh, &wsh, &tr, &op_return] {
+            assert_eq!(
+                chain.dust_limit_for_script(script),
+                script.dust_value().to_sat()
+            );
+        }
+
+        let policy = RelayPolicy {
+            dust_relay_fee: 1000,
+            ..RelayPolicy::BITCOIN_CORE
+        };
+        assert_eq!(policy.dust_limit_for(22, true), 98);
+        assert_eq!(policy.dust_limit_for(253, false), 253 + 3 + 8 + 148);
+        let policy = RelayPolicy {
+            dust_relay_fee: 0,
+            ..RelayPolicy::BITCOIN_CORE
+        };
+        assert_eq!(policy.dust_limit_for(25, false), 0);
+    }
+
+    #[test]
+    fn relay_fees() {
+        let policy = Chain::Mainnet.chain_params().relay_policy;
+        assert_eq!(policy, RelayPolicy::BITCOIN_CORE);
+        assert_eq!(policy.min_relay_fee_for(141), 141);
+        assert!(policy.is_standard_weight(400_000));
+        assert!(!policy.is_standard_weight(400_001));
+
+        let liquid = Chain::LiquidV1.chain_params().relay_policy;
+        assert_eq!(liquid.min_relay_fee_for(2500), 250);
+        assert_eq!(liquid.min_relay_fee_for(5), 1);
+        assert_eq!(liquid.min_relay_fee_for(0), 0);
+    }
+}
@@ -0,0 +1,322 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Process-wide registry of custom chains, which allows to resolve chains
+//! declared by one part of an application by their name or genesis hash in
+//! any other part of it.
+
+use std::collections::BTreeMap;
+#[cfg(test)]
+use std::sync::{Mutex, MutexGuard};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use bitcoin::BlockHash;
+use once_cell::sync::Lazy;
+
+use crate::{Chain, ChainParams, HRP_CHAIN};
+
+static REGISTRY: Lazy<RwLock<ChainRegistry>> =
+    Lazy::new(|| RwLock::new(ChainRegistry::default()));
+
+/// Lock taken by tests which register custom chains or depend on the set of
+/// [`Chain::known`] chains, since the registry is shared by all tests
+#[cfg(test)]
+pub(crate) fn test_lock() -> MutexGuard<'static, ()> {
+    static LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Errors registering custom chains
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum RegistryError {
+    /// `{0}` is not a valid chain name or alias: it must be a non-empty
+    /// lowercase string not containing `:` character and not starting with
+    /// `chain1` prefix
+    InvalidName(String),
+
+    /// chain name or alias `{0}` is already used by another chain
+    NameTaken(String),
+
+    /// chain with genesis hash {0} is already registered
+    GenesisTaken(BlockHash),
+}
+
+/// Process-wide registry of custom chains.
+///
+/// Chains registered here are resolved by [`Chain::from_str`] (using chain
+/// name or any of the registered aliases) and [`Chain::from_genesis_hash`]
+/// after the built-in chains, and are returned as [`Chain::Other`].
+///
+/// [`Chain::from_str`]: std::str::FromStr::from_str
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ChainRegistry {
+    chains: BTreeMap<String, ChainParams>,
+    aliases: BTreeMap<String, String>,
+}
+
+impl ChainRegistry {
+    fn read() -> RwLockReadGuard<'static, ChainRegistry> {
+        REGISTRY.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write() -> RwLockWriteGuard<'static, ChainRegistry> {
+        REGISTRY.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_builtin_name(name: &str) -> bool {
+        Chain::builtin()
+            .iter()
+            .map(Chain::chain_params)
+            .any(|params| {
+                params.name == name
+                    || params.bip70_name == name
+                    || params.bip173_prefix == name
+            })
+    }
+
+    fn is_builtin_genesis(hash: &BlockHash) -> bool {
+        Chain::builtin()
+            .iter()
+            .any(|chain| chain.as_genesis_hash() == hash)
+    }
+
+    fn is_taken(&self, name: &str) -> bool {
+        ChainRegistry::is_builtin_name(name)
+            || self.chains.contains_key(name)
+            || self.aliases.contains_key(name)
+    }
+
+    /// Registers custom chain under its [`ChainParams::name`] and a list of
+    /// additional aliases. Names are case-insensitive. Genesis hash of the
+    /// chain must differ from the genesis of other registered chains, unless
+    /// it is a genesis of a built-in chain (like in private regtests and
+    /// custom signets).
+    pub fn register(
+        params: ChainParams,
+        aliases: &[&str],
+    ) -> Result<(), RegistryError> {
+        let name = params.name.to_ascii_lowercase();
+        let aliases = aliases
+            .iter()
+            .map(|alias| alias.to_ascii_lowercase())
+            .collect::<Vec<_>>();
+
+        let mut registry = ChainRegistry::write();
+        for alias in std::iter::once(&name).chain(&aliases) {
+            if alias.is_empty()
+                || alias.contains(':')
+                || alias.starts_with(&format!("{}1", HRP_CHAIN))
+            {
+                return Err(RegistryError::InvalidName(alias.clone()));
+            }
+            if registry.is_taken(alias) {
+                return Err(RegistryError::NameTaken(alias.clone()));
+            }
+        }
+        // Chains sharing genesis with a built-in chain are never resolved by
+        // their genesis hash, so they do not conflict with each other
+        if !ChainRegistry::is_builtin_genesis(&params.genesis_hash)
+            && registry
+                .chains
+                .values()
+                .any(|other| other.genesis_hash == params.genesis_hash)
+        {
+            return Err(RegistryError::GenesisTaken(params.genesis_hash));
+        }
+
+        for alias in aliases {
+            registry.aliases.insert(alias, name.clone());
+        }
+        registry.chains.insert(name, params);
+        Ok(())
+    }
+
+    /// Removes custom chain with a given name (but not alias) from the
+    /// registry, returning its parameters if it was registered
+    pub fn unregister(name: &str) -> Option<ChainParams> {
+        let name = name.to_ascii_lowercase();
+        let mut registry = ChainRegistry::write();
+        registry.aliases.retain(|_, chain| *chain != name);
+        registry.chains.remove(&name)
+    }
+
+    /// Returns parameters of a registered chain using its name or alias
+    pub fn get(name: &str) -> Option<ChainParams> {
+        let name = name.to_ascii_lowercase();
+        let registry = ChainRegistry::read();
+        let name = registry.aliases.get(&name).unwrap_or(&name);
+        registry.chains.get(name).cloned()
+    }
+
+    /// Returns parameters of a registered chain with a given genesis hash.
+    /// Chains sharing genesis with a built-in chain are resolved only by
+    /// their names, so `None` is returned for the built-in genesis hashes.
+    pub fn by_genesis_hash(hash: &BlockHash) -> Option<ChainParams> {
+        if ChainRegistry::is_builtin_genesis(hash) {
+            return None;
+        }
+        ChainRegistry::read()
+            .chains
+            .values()
+            .find(|params| params.genesis_hash == *hash)
+            .cloned()
+    }
+
+    /// Lists parameters of all registered chains, ordered by chain names
+    pub fn list() -> Vec<ChainParams> {
+        ChainRegistry::read().chains.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use bitcoin::hashes::{sha256d, Hash};
+    use bitcoin::Script;
+
+    use super::*;
+    use crate::{KeyApplication, CHAIN_PARAMS_REGTEST};
+
+    fn custom_params(name: &str) -> ChainParams {
+        let mut params = CHAIN_PARAMS_REGTEST.clone();
+        params.name = name.to_owned();
+        params.genesis_hash =
+            BlockHash::from_hash(sha256d::Hash::hash(name.as_bytes()));
+        params
+    }
+
+    #[test]
+    fn resolve() {
+        let _lock = crate::registry::test_lock();
+        let params = custom_params("registry-resolve");
+        let hash = params.genesis_hash;
+        assert_eq!(Chain::from_genesis_hash(&hash), None);
+        assert!(Chain::from_str("registry-resolve").is_err());
+
+        ChainRegistry::register(params.clone(), &["Reg-Alias"]).unwrap();
+        let chain = Chain::Other(Box::new(params.clone()));
+        assert_eq!(Chain::from_str("registry-resolve").unwrap(), chain);
+        assert_eq!(Chain::from_str("REGISTRY-RESOLVE").unwrap(), chain);
+        assert_eq!(Chain::from_str("reg-alias").unwrap(), chain);
+        assert_eq!(Chain::from_genesis_hash(&hash), Some(chain));
+        assert_eq!(ChainRegistry::get("reg-alias"), Some(params.clone()));
+        assert!(ChainRegistry::list().contains(&params));
+
+        // Built-in chains take precedence
+        assert_eq!(Chain::from_str("bitcoin").unwrap(), Chain::Mainnet);
+
+        assert_eq!(ChainRegistry::unregister("reg-alias"), None);
+        assert_eq!(ChainRegistry::unregister("registry-resolve"), Some(params));
+        assert!(Chain::from_str("reg-alias").is_err());
+        assert_eq!(Chain::from_genesis_hash(&hash), None);
+    }
+
+    #[test]
+    fn conflicts() {
+        let _lock = crate::registry::test_lock();
+        let params = custom_params("registry-conflicts");
+        assert_eq!(
+            ChainRegistry::register(params.clone(), &["testnet"]),
+            Err(RegistryError::NameTaken(s!("testnet")))
+        );
+        assert_eq!(
+            ChainRegistry::register(params.clone(), &["bc"]),
+            Err(RegistryError::NameTaken(s!("bc")))
+        );
+        assert_eq!(
+            ChainRegistry::register(params.clone(), &["other:x"]),
+            Err(RegistryError::InvalidName(s!("other:x")))
+        );
+        assert_eq!(
+            ChainRegistry::register(params.clone(), &["chain1x"]),
+            Err(RegistryError::InvalidName(s!("chain1x")))
+        );
+        assert_eq!(
+            ChainRegistry::register(params.clone(), &[""]),
+            Err(RegistryError::InvalidName(s!("")))
+        );
+        assert_eq!(ChainRegistry::get("registry-conflicts"), None);
+
+        ChainRegistry::register(params.clone(), &[]).unwrap();
+        assert_eq!(
+            ChainRegistry::register(params.clone(), &[]),
+            Err(RegistryError::NameTaken(s!("registry-conflicts")))
+        );
+        let mut same_genesis = params.clone();
+        same_genesis.name = s!("registry-conflicts-2");
+        assert_eq!(
+            ChainRegistry::register(same_genesis, &[]),
+            Err(RegistryError::GenesisTaken(params.genesis_hash))
+        );
+        ChainRegistry::unregister("registry-conflicts").unwrap();
+    }
+
+    #[test]
+    fn builtin_genesis() {
+        let _lock = crate::registry::test_lock();
+        let mut private = CHAIN_PARAMS_REGTEST.clone();
+        private.name = s!("registry-private");
+        let mut team = CHAIN_PARAMS_REGTEST.clone();
+        team.name = s!("registry-team");
+        let mut signet = Chain::signet_with_challenge(Script::from(vec![0x51]))
+            .chain_params();
+        signet.name = s!("registry-signet");
+
+        for params in [&private, &team, &signet] {
+            ChainRegistry::register(params.clone(), &[]).unwrap();
+            assert_eq!(
+                Chain::from_str(&params.name).unwrap(),
+                Chain::Other(Box::new(params.clone()))
+            );
+            assert_eq!(ChainRegistry::get(&params.name).as_ref(), Some(params));
+            assert_eq!(
+                ChainRegistry::by_genesis_hash(&params.genesis_hash),
+                None
+            );
+        }
+        assert_eq!(
+            Chain::from_genesis_hash(&private.genesis_hash),
+            Some(Chain::Regtest(private.genesis_hash))
+        );
+        assert_eq!(
+            Chain::from_genesis_hash(&signet.genesis_hash),
+            Some(Chain::Signet)
+        );
+
+        // Detection reports registered chains after the built-in ones
+        let names = |chains: Vec<Chain>| {
+            chains
+                .into_iter()
+                .map(|chain| chain.chain_params().name)
+                .collect::<Vec<_>>()
+        };
+        let (chains, _) =
+            Chain::from_address("bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080")
+                .unwrap();
+        assert_eq!(names(chains), vec![
+            s!("regtest"),
+            s!("registry-private"),
+            s!("registry-team")
+        ]);
+        let version =
+            signet.bip32_versions.version(KeyApplication::Hashed).public;
+        let (chains, _) = Chain::detect_xkey_version(version).unwrap();
+        assert!(names(chains).contains(&s!("registry-signet")));
+
+        for params in [private, team, signet] {
+            assert_eq!(ChainRegistry::unregister(&params.name), Some(params));
+        }
+    }
+}
@@ -0,0 +1,358 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Bootstrap peer data of a chain: DNS seeds and fixed seed addresses.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+
+use strict_encoding::{StrictDecode, StrictEncode};
+
+/// Address type tag of IPv4 fixed seeds, matching BIP155 network id
+const ADDR_IPV4: u8 = 1;
+/// Address type tag of IPv6 fixed seeds, matching BIP155 network id
+const ADDR_IPV6: u8 = 2;
+
+/// Fixed seeds snapshots of the built-in chains, see `contrib/seeds`
+const FIXED_SEEDS_MAINNET: &str =
+    include_str!("../contrib/seeds/nodes_main.txt");
+const FIXED_SEEDS_TESTNET: &str =
+    include_str!("../contrib/seeds/nodes_test.txt");
+const FIXED_SEEDS_SIGNET: &str =
+    include_str!("../contrib/seeds/nodes_signet.txt");
+
+/// Errors parsing fixed seed lists
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum SeedError {
+    /// invalid fixed seed address `{1}` at line {0}
+    InvalidAddress(usize, String),
+}
+
+/// Bootstrap peer data of a chain, used by P2P nodes to discover their first
+/// peers.
+///
+/// Fixed seeds of the built-in chains come from snapshots of Bitcoin Core
+/// `contrib/seeds` data vendored in `contrib/seeds` directory of this crate;
+/// testnet4 provides DNS seeds only.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+pub struct ChainSeeds {
+    /// Host names of DNS seeds, resolving into addresses of the reachable
+    /// nodes
+    pub dns: Vec<String>,
+
+    /// Fixed addresses of the nodes, which are used when DNS seeds are not
+    /// available
+    pub fixed: Vec<SocketAddr>,
+}
+
+impl ChainSeeds {
+    /// Seeds of bitcoin mainnet
+    pub fn mainnet() -> ChainSeeds {
+        ChainSeeds::with_fixed(FIXED_SEEDS_MAINNET, 8333, &[
+            "seed.bitcoin.sipa.be",
+            "dnsseed.bluematt.me",
+            "seed.bitcoin.jonasschnelli.ch",
+            "seed.btc.petertodd.net",
+            "seed.bitcoin.sprovoost.nl",
+            "dnsseed.emzy.de",
+            "seed.bitcoin.wiz.biz",
+            "seed.mainnet.achownodes.xyz",
+        ])
+    }
+
+    /// Seeds of bitcoin testnet3
+    pub fn testnet() -> ChainSeeds {
+        ChainSeeds::with_fixed(FIXED_SEEDS_TESTNET, 18333, &[
+            "testnet-seed.bitcoin.jonasschnelli.ch",
+            "seed.tbtc.petertodd.net",
+            "seed.testnet.bitcoin.sprovoost.nl",
+            "testnet-seed.bluematt.me",
+            "seed.testnet.achownodes.xyz",
+        ])
+    }
+
+    /// Seeds of bitcoin testnet4
+    pub fn testnet4() -> ChainSeeds {
+        ChainSeeds::with_dns(&[
+            "seed.testnet4.bitcoin.sprovoost.nl",
+            "seed.testnet4.wiz.biz",
+        ])
+    }
+
+    /// Seeds of the default bitcoin signet
+    pub fn signet() -> ChainSeeds {
+        ChainSeeds::with_fixed(FIXED_SEEDS_SIGNET, 38333, &[
+            "seed.signet.bitcoin.sprovoost.nl",
+            "seed.signet.achownodes.xyz",
+        ])
+    }
+
+    fn with_dns(hosts: &[&str]) -> ChainSeeds {
+        ChainSeeds {
+            dns: hosts.iter().map(|host| host.to_string()).collect(),
+            fixed: vec![],
+        }
+    }
+
+    fn with_fixed(
+        fixed: &str,
+        default_port: u16,
+        hosts: &[&str],
+    ) -> ChainSeeds {
+        ChainSeeds {
+            fixed: ChainSeeds::parse_fixed(fixed, default_port)
+                .expect("vendored fixed seeds are valid"),
+            ..ChainSeeds::with_dns(hosts)
+        }
+    }
+
+    /// Detects whether no seeds are known
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.dns.is_empty() && self.fixed.is_empty()
+    }
+
+    /// Parses list of fixed seeds in the format used by Bitcoin Core
+    /// `contrib/seeds` tooling: one address per line, optionally with a port
+    /// (IPv6 addresses with port must be enclosed in brackets), with empty
+    /// lines and `#` comments ignored. Addresses without port get a given
+    /// default port. Overlay network addresses (`.onion`, `.i2p`) are not
+    /// supported and are skipped.
+    pub fn parse_fixed(
+        s: &str,
+        default_port: u16,
+    ) -> Result<Vec<SocketAddr>, SeedError> {
+        let mut seeds = vec![];
+        for (no, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let host =
+                line.rsplit_once(':').map(|(host, _)| host).unwrap_or(line);
+            if line.is_empty()
+                || host.ends_with(".onion")
+                || host.ends_with(".i2p")
+            {
+                continue;
+            }
+            let addr = SocketAddr::from_str(line)
+                .or_else(|_| {
+                    IpAddr::from_str(
+                        line.trim_matches(|c| c == '[' || c == ']'),
+                    )
+                    .map(|ip| SocketAddr::new(ip, default_port))
+                })
+                .map_err(|_| {
+                    SeedError::InvalidAddress(no + 1, line.to_owned())
+                })?;
+            seeds.push(addr);
+        }
+        Ok(seeds)
+    }
+}
+
+impl StrictEncode for ChainSeeds {
+    fn strict_encode<E: io::Write>(
+        &self,
+        mut e: E,
+    ) -> Result<usize, strict_encoding::Error> {
+        if self.fixed.len() > u16::MAX as usize {
+            return Err(strict_encoding::Error::ExceedMaxItems(
+                self.fixed.len(),
+            ));
+        }
+        let mut len = self.dns.strict_encode(&mut e)?;
+        // Fixed seeds are encoded compactly, using BIP155 network id followed
+        // by the address bytes and the port
+        len += (self.fixed.len() as u16).strict_encode(&mut e)?;
+        for addr in &self.fixed {
+            let (tag, octets) = match addr.ip() {
+                IpAddr::V4(ip) => (ADDR_IPV4, ip.octets().to_vec()),
+                IpAddr::V6(ip) => (ADDR_IPV6, ip.octets().to_vec()),
+            };
+            len += tag.strict_encode(&mut e)?;
+            e.write_all(&octets)?;
+            len += octets.len();
+            len += addr.port().strict_encode(&mut e)?;
+        }
+        Ok(len)
+    }
+}
+
+impl StrictDecode for ChainSeeds {
+    fn strict_decode<D: io::Read>(
+        mut d: D,
+    ) -> Result<Self, strict_encoding::Error> {
+        let dns = Vec::<String>::strict_decode(&mut d)?;
+        let count = u16::strict_decode(&mut d)?;
+        let mut fixed = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let ip = match u8::strict_decode(&mut d)? {
+                ADDR_IPV4 => {
+                    let mut octets = [0u8; 4];
+                    d.read_exact(&mut octets)?;
+                    IpAddr::V4(Ipv4Addr::from(octets))
+                }
+                ADDR_IPV6 => {
+                    let mut octets = [0u8; 16];
+                    d.read_exact(&mut octets)?;
+                    IpAddr::V6(Ipv6Addr::from(octets))
+                }
+                other => {
+                    return Err(strict_encoding::Error::EnumValueNotKnown(
+                        "fixed seed address type",
+                        other as usize,
+                    ))
+                }
+            };
+            fixed.push(SocketAddr::new(ip, u16::strict_decode(&mut d)?));
+        }
+        Ok(ChainSeeds { dns, fixed })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use bitcoin::Script;
+    use strict_encoding::{strict_deserialize, strict_serialize};
+
+    use super::*;
+    use crate::{Chain, CHAIN_PARAMS_REGTEST};
+
+    fn custom_seeds() -> ChainSeeds {
+        ChainSeeds {
+            dns: vec![s!("seed.example.com")],
+            fixed: vec![
+                SocketAddr::from_str("1.2.3.4:8333").unwrap(),
+                SocketAddr::from_str("[2001:db8::1]:18333").unwrap(),
+            ],
+        }
+    }
+
+    #[test]
+    fn builtin() {
+        for chain in [
+            Chain::Mainnet,
+            Chain::Testnet3,
+            Chain::Testnet4,
+            Chain::Signet,
+        ] {
+            assert!(!chain.chain_params().seeds.dns.is_empty());
+        }
+        assert!(CHAIN_PARAMS_REGTEST.seeds.is_empty());
+        assert!(Chain::LiquidV1.chain_params().seeds.is_empty());
+        // Custom signets are not reachable through the default signet seeds
+        let genesis = *Chain::Signet.as_genesis_hash();
+        assert!(Chain::SignetCustom(genesis).chain_params().seeds.is_empty());
+        assert!(Chain::signet_with_challenge(Script::from(vec![0x51]))
+            .chain_params()
+            .seeds
+            .is_empty());
+    }
+
+    #[test]
+    fn vendored() {
+        for (data, seeds) in [
+            (FIXED_SEEDS_MAINNET, ChainSeeds::mainnet()),
+            (FIXED_SEEDS_TESTNET, ChainSeeds::testnet()),
+            (FIXED_SEEDS_SIGNET, ChainSeeds::signet()),
+        ] {
+            assert_eq!(
+                ChainSeeds::parse_fixed(data, 0).unwrap().len(),
+                seeds.fixed.len()
+            );
+            let unique = seeds.fixed.iter().collect::<BTreeSet<_>>();
+            assert_eq!(unique.len(), seeds.fixed.len());
+            assert!(seeds.fixed.iter().all(|addr| !addr.ip().is_unspecified()
+                && !addr.ip().is_loopback()
+                && addr.port() != 0));
+        }
+        assert!(ChainSeeds::testnet4().fixed.is_empty());
+    }
+
+    #[test]
+    fn encoding() {
+        let seeds = custom_seeds();
+        let data = strict_serialize(&seeds).unwrap();
+        assert_eq!(
+            data.len(),
+            strict_serialize(&seeds.dns).unwrap().len() + 2 + 7 + 19
+        );
+        assert_eq!(strict_deserialize::<ChainSeeds>(&data).unwrap(), seeds);
+
+        let mut data = strict_serialize(&ChainSeeds {
+            dns: vec![],
+            fixed: vec![SocketAddr::from_str("1.2.3.4:1").unwrap()],
+        })
+        .unwrap();
+        data[4] = 4;
+        assert!(matches!(
+            strict_deserialize::<ChainSeeds>(&data),
+            Err(strict_encoding::Error::EnumValueNotKnown(_, 4))
+        ));
+
+        let mut params = Chain::Mainnet.chain_params();
+        params.seeds = seeds;
+        let data = strict_serialize(&params).unwrap();
+        let decoded: crate::ChainParams = strict_deserialize(&data).unwrap();
+        assert_eq!(decoded.seeds, params.seeds);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let seeds = custom_seeds();
+        let json = serde_json::to_string(&seeds).unwrap();
+        assert_eq!(
+            json,
+            r#"{"dns":["seed.example.com"],"fixed":["1.2.3.4:8333","[2001:db8::1]:18333"]}"#
+        );
+        assert_eq!(serde_json::from_str::<ChainSeeds>(&json).unwrap(), seeds);
+    }
+
+    #[test]
+    fn parse_fixed() {
+        let onion = format!("{}.onion:8333", "x".repeat(56));
+        let i2p = format!("{}.b32.i2p:0", "y".repeat(52));
+        let list = [
+            "# Fixed seeds",
+            "1.2.3.4:8333",
+            "",
+            "5.6.7.8 # default port",
+            "[2001:db8::1]:18333",
+            "2001:db8::2",
+            "[2001:db8::3]",
+            &onion,
+            &i2p,
+        ]
+        .join("\n");
+        assert_eq!(ChainSeeds::parse_fixed(&list, 8333).unwrap(), vec![
+            SocketAddr::from_str("1.2.3.4:8333").unwrap(),
+            SocketAddr::from_str("5.6.7.8:8333").unwrap(),
+            SocketAddr::from_str("[2001:db8::1]:18333").unwrap(),
+            SocketAddr::from_str("[2001:db8::2]:8333").unwrap(),
+            SocketAddr::from_str("[2001:db8::3]:8333").unwrap(),
+        ]);
+        assert_eq!(
+            ChainSeeds::parse_fixed("1.2.3.4:8333\nseed.example.com", 8333),
+            Err(SeedError::InvalidAddress(2, s!("seed.example.com")))
+        );
+    }
+}
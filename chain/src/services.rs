@@ -0,0 +1,380 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Default ports of the network services operating on top of a chain and
+//! their per-user overrides.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::Chain;
+
+/// Default ports of the network services operating on a chain; zero port
+/// means that the service has no default port on the chain
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+#[derive(StrictEncode, StrictDecode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", deny_unknown_fields)
+)]
+pub struct ServicePorts {
+    /// Electrum server port for plain TCP connections
+    pub electrum_tcp: u16,
+
+    /// Electrum server port for TLS connections
+    pub electrum_ssl: u16,
+
+    /// Esplora REST API port
+    pub esplora: u16,
+
+    /// Lightning network peer port
+    pub lightning: u16,
+
+    /// Node ZMQ port publishing raw blocks. Nodes do not enable ZMQ by
+    /// default, so the defaults follow the widespread convention.
+    pub zmq_block: u16,
+
+    /// Node ZMQ port publishing raw transactions
+    pub zmq_tx: u16,
+}
+
+impl ServicePorts {
+    /// Service ports of bitcoin mainnet
+    pub const MAINNET: ServicePorts = ServicePorts {
+        electrum_tcp: 50001,
+        electrum_ssl: 50002,
+        esplora: 3000,
+        lightning: 9735,
+        zmq_block: 28332,
+        zmq_tx: 28333,
+    };
+
+    /// Service ports of bitcoin testnet3
+    pub const TESTNET: ServicePorts = ServicePorts {
+        electrum_tcp: 60001,
+        electrum_ssl: 60002,
+        esplora: 3001,
+        lightning: 19735,
+        ..ServicePorts::MAINNET
+    };
+
+    /// Service ports of bitcoin testnet4
+    pub const TESTNET4: ServicePorts = ServicePorts {
+        electrum_tcp: 40001,
+        electrum_ssl: 40002,
+        esplora: 3004,
+        lightning: 49735,
+        ..ServicePorts::MAINNET
+    };
+
+    /// Service ports of bitcoin regtest. ZMQ ports of other chains are taken
+    /// by regtest P2P and RPC, so the ones following them are used.
+    pub const REGTEST: ServicePorts = ServicePorts {
+        electrum_tcp: 60401,
+        electrum_ssl: 60402,
+        esplora: 3002,
+        lightning: 19846,
+        zmq_block: 28334,
+        zmq_tx: 28335,
+    };
+
+    /// Service ports of bitcoin signet
+    pub const SIGNET: ServicePorts = ServicePorts {
+        electrum_tcp: 60601,
+        electrum_ssl: 60602,
+        esplora: 3003,
+        lightning: 39735,
+        ..ServicePorts::MAINNET
+    };
+
+    /// Service ports of Liquid V1 sidechain
+    pub const LIQUIDV1: ServicePorts = ServicePorts {
+        electrum_tcp: 51000,
+        electrum_ssl: 51001,
+        esplora: 3000,
+        lightning: 9735,
+        ..ServicePorts::MAINNET
+    };
+
+    /// Service ports of Liquid testnet sidechain
+    pub const LIQUID_TESTNET: ServicePorts = ServicePorts {
+        electrum_tcp: 51301,
+        electrum_ssl: 51302,
+        esplora: 3001,
+        lightning: 19735,
+        ..ServicePorts::MAINNET
+    };
+}
+
+/// Network services which may operate on top of a chain
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "kebab-case")
+)]
+pub enum Service {
+    /// Node P2P protocol
+    #[display("p2p")]
+    P2p,
+
+    /// Node JSON-RPC interface
+    #[display("rpc")]
+    Rpc,
+
+    /// Electrum server over plain TCP
+    #[display("electrum-tcp")]
+    ElectrumTcp,
+
+    /// Electrum server over TLS
+    #[display("electrum-ssl")]
+    ElectrumSsl,
+
+    /// Esplora REST API
+    #[display("esplora")]
+    Esplora,
+
+    /// Lightning network peer protocol
+    #[display("lightning")]
+    Lightning,
+
+    /// Node ZMQ notifications with raw blocks
+    #[display("zmq-block")]
+    ZmqBlock,
+
+    /// Node ZMQ notifications with raw transactions
+    #[display("zmq-tx")]
+    ZmqTx,
+}
+
+impl Service {
+    /// Lists all services
+    pub fn all() -> &'static [Service] {
+        &[
+            Service::P2p,
+            Service::Rpc,
+            Service::ElectrumTcp,
+            Service::ElectrumSsl,
+            Service::Esplora,
+            Service::Lightning,
+            Service::ZmqBlock,
+            Service::ZmqTx,
+        ]
+    }
+}
+
+/// Error parsing [`Service`] name
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display("unknown network service `{0}`")]
+pub struct UnknownService(pub String);
+
+impl FromStr for Service {
+    type Err = UnknownService;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Service::all()
+            .iter()
+            .find(|service| service.to_string().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| UnknownService(s.to_owned()))
+    }
+}
+
+/// Per-user overrides of the default service ports, keyed by chain name
+/// ([`crate::ChainParams::name`]) and service. With serde, overrides are
+/// represented as a map of chain names to maps of service names to ports.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct PortOverrides(BTreeMap<String, BTreeMap<Service, u16>>);
+
+impl PortOverrides {
+    /// Constructs empty set of overrides
+    #[inline]
+    pub fn new() -> PortOverrides { PortOverrides::default() }
+
+    /// Overrides port of a service on a given chain, returning previous
+    /// override
+    pub fn set(
+        &mut self,
+        chain: &Chain,
+        service: Service,
+        port: u16,
+    ) -> Option<u16> {
+        self.0
+            .entry(chain.chain_params().name)
+            .or_default()
+            .insert(service, port)
+    }
+
+    /// Removes override of a service port on a given chain
+    pub fn remove(&mut self, chain: &Chain, service: Service) -> Option<u16> {
+        let name = chain.chain_params().name;
+        let ports = self.0.get_mut(&name)?;
+        let port = ports.remove(&service);
+        if ports.is_empty() {
+            self.0.remove(&name);
+        }
+        port
+    }
+
+    /// Returns overridden port of a service on a given chain
+    pub fn get(&self, chain: &Chain, service: Service) -> Option<u16> {
+        self.0
+            .get(&chain.chain_params().name)?
+            .get(&service)
+            .copied()
+    }
+}
+
+impl Chain {
+    /// Returns default port of a service on the chain, or `None` if the
+    /// service has no default port on it
+    pub fn default_port(&self, service: Service) -> Option<u16> {
+        let params = self.chain_params();
+        let ports = params.service_ports;
+        let port = match service {
+            Service::P2p => params.p2p_port,
+            Service::Rpc => params.rpc_port,
+            Service::ElectrumTcp => ports.electrum_tcp,
+            Service::ElectrumSsl => ports.electrum_ssl,
+            Service::Esplora => ports.esplora,
+            Service::Lightning => ports.lightning,
+            Service::ZmqBlock => ports.zmq_block,
+            Service::ZmqTx => ports.zmq_tx,
+        };
+        Some(port).filter(|port| *port != 0)
+    }
+
+    /// Resolves port of a service on the chain, preferring user override
+    /// over the chain default
+    pub fn service_port(
+        &self,
+        service: Service,
+        overrides: &PortOverrides,
+    ) -> Option<u16> {
+        overrides
+            .get(self, service)
+            .filter(|port| *port != 0)
+            .or_else(|| self.default_port(service))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        assert_eq!(Chain::Mainnet.default_port(Service::P2p), Some(8333));
+        assert_eq!(Chain::Mainnet.default_port(Service::Rpc), Some(8332));
+        assert_eq!(Chain::Mainnet.default_port(Service::Lightning), Some(9735));
+        assert_eq!(
+            Chain::Testnet3.default_port(Service::Lightning),
+            Some(19735)
+        );
+        assert_eq!(
+            Chain::Signet.default_port(Service::ElectrumTcp),
+            Some(60601)
+        );
+        assert_eq!(Chain::LiquidV1.default_port(Service::Esplora), Some(3000));
+
+        let mut params = Chain::Mainnet.chain_params();
+        params.service_ports.esplora = 0;
+        assert_eq!(
+            Chain::Other(Box::new(params)).default_port(Service::Esplora),
+            None
+        );
+    }
+
+    #[test]
+    fn distinct_defaults() {
+        for chain in Chain::builtin() {
+            let ports = Service::all()
+                .iter()
+                .filter_map(|service| chain.default_port(*service))
+                .collect::<Vec<_>>();
+            let mut distinct = ports.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(ports.len(), distinct.len(), "{}", chain);
+        }
+    }
+
+    #[test]
+    fn overrides() {
+        let mut overrides = PortOverrides::new();
+        assert_eq!(
+            overrides.set(&Chain::Mainnet, Service::ElectrumTcp, 50011),
+            None
+        );
+        assert_eq!(
+            overrides.set(&Chain::Mainnet, Service::ElectrumTcp, 50021),
+            Some(50011)
+        );
+        assert_eq!(
+            Chain::Mainnet.service_port(Service::ElectrumTcp, &overrides),
+            Some(50021)
+        );
+        assert_eq!(
+            Chain::Mainnet.service_port(Service::ElectrumSsl, &overrides),
+            Some(50002)
+        );
+        assert_eq!(
+            Chain::Testnet3.service_port(Service::ElectrumTcp, &overrides),
+            Some(60001)
+        );
+
+        assert_eq!(
+            overrides.remove(&Chain::Mainnet, Service::ElectrumTcp),
+            Some(50021)
+        );
+        assert_eq!(overrides, PortOverrides::new());
+        assert_eq!(
+            Chain::Mainnet.service_port(Service::ElectrumTcp, &overrides),
+            Some(50001)
+        );
+    }
+
+    #[test]
+    fn service_names() {
+        for service in Service::all() {
+            assert_eq!(Service::from_str(&service.to_string()), Ok(*service));
+        }
+        assert_eq!(Service::from_str("ZMQ-TX"), Ok(Service::ZmqTx));
+        assert_eq!(Service::from_str("zmq"), Err(UnknownService(s!("zmq"))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let overrides: PortOverrides = serde_json::from_str(
+            r#"{ "testnet": { "electrum-ssl": 51002, "lightning": 9736 } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Chain::Testnet3.service_port(Service::ElectrumSsl, &overrides),
+            Some(51002)
+        );
+        assert_eq!(
+            Chain::Testnet3.service_port(Service::Lightning, &overrides),
+            Some(9736)
+        );
+    }
+}
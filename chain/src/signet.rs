@@ -0,0 +1,155 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2020-2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Signet networks defined by their challenge script (BIP325).
+
+use bitcoin::hashes::{sha256d, Hash};
+use bitcoin::{consensus, Script};
+
+use crate::{Chain, P2pMagicNumber, P2pNetworkId, CHAIN_PARAMS_SIGNET};
+
+/// Challenge script of the default bitcoin signet
+pub const SIGNET_CHALLENGE_DEFAULT: &[u8] = &[
+    0x51, 0x21, 0x03, 0xad, 0x5e, 0x0e, 0xda, 0xd1, 0x8c, 0xb1, 0xf0, 0xfc,
+    0x0d, 0x28, 0xa3, 0xd4, 0xf1, 0xf3, 0xe4, 0x45, 0x64, 0x03, 0x37, 0x48,
+    0x9a, 0xbb, 0x10, 0x40, 0x4f, 0x2d, 0x1e, 0x08, 0x6b, 0xe4, 0x30, 0x21,
+    0x03, 0x59, 0xef, 0x50, 0x21, 0x96, 0x4f, 0xe2, 0x2d, 0x6f, 0x8e, 0x05,
+    0xb2, 0x46, 0x3c, 0x95, 0x40, 0xce, 0x96, 0x88, 0x3f, 0xe3, 0xb2, 0x78,
+    0x76, 0x0f, 0x04, 0x8f, 0x51, 0x89, 0xf2, 0xe6, 0xc4, 0x52, 0xae,
+];
+
+/// Derives P2P network magic number of a signet from its challenge script in
+/// the same way as Bitcoin Core does: it is the first four bytes of the
+/// double SHA256 hash of the consensus-serialized challenge script.
+pub fn signet_magic(challenge: &Script) -> P2pMagicNumber {
+    let hash = sha256d::Hash::hash(&consensus::serialize(challenge));
+    let mut magic = [0u8; 4];
+    magic.copy_from_slice(&hash[..4]);
+    P2pMagicNumber::from_le_bytes(magic)
+}
+
+impl Chain {
+    /// Constructs signet chain from its challenge script, matching the value
+    /// of `-signetchallenge` argument used by bitcoind. All signets share the
+    /// same genesis block and are distinguished by the challenge script and
+    /// the network magic number derived from it.
+    pub fn signet_with_challenge(challenge: Script) -> Chain {
+        if challenge.as_bytes() == SIGNET_CHALLENGE_DEFAULT {
+            Chain::Signet
+        } else {
+            Chain::SignetChallenge(challenge)
+        }
+    }
+
+    /// Returns challenge script for signet chains, or `None` for other chains
+    /// or signets with unknown challenge
+    #[inline]
+    pub fn signet_challenge(&self) -> Option<Script> {
+        self.as_signet_challenge().cloned()
+    }
+
+    /// Returns reference to the challenge script for signet chains, or `None`
+    /// for other chains or signets with unknown challenge
+    pub fn as_signet_challenge(&self) -> Option<&Script> {
+        match self {
+            Chain::Signet => CHAIN_PARAMS_SIGNET.signet_challenge.as_ref(),
+            Chain::SignetChallenge(challenge) => Some(challenge),
+            Chain::Other(params) => params.signet_challenge.as_ref(),
+            Chain::Mainnet
+            | Chain::Testnet3
+            | Chain::Testnet4
+            | Chain::Regtest(_)
+            | Chain::SignetCustom(_)
+            | Chain::LiquidV1
+            | Chain::LiquidTestnet => None,
+        }
+    }
+}
+
+impl P2pNetworkId {
+    /// Constructs network id for a signet with a given challenge script
+    #[inline]
+    pub fn with_signet_challenge(challenge: &Script) -> P2pNetworkId {
+        P2pNetworkId::from_magic(signet_magic(challenge))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use bitcoin::hashes::hex::{FromHex, ToHex};
+    use strict_encoding::{strict_deserialize, strict_serialize};
+
+    use super::*;
+
+    #[test]
+    fn magic() {
+        let default = Script::from(SIGNET_CHALLENGE_DEFAULT.to_vec());
+        assert_eq!(signet_magic(&default), P2pNetworkId::Signet.as_magic());
+        assert_eq!(
+            P2pNetworkId::with_signet_challenge(&default),
+            P2pNetworkId::Signet
+        );
+
+        let op_true = Script::from_hex("51").unwrap();
+        assert_eq!(signet_magic(&op_true), 0xbd6fd254);
+        assert_eq!(
+            P2pNetworkId::with_signet_challenge(&op_true),
+            P2pNetworkId::Other(0xbd6fd254)
+        );
+    }
+
+    #[test]
+    fn custom_signet() {
+        let default = Script::from(SIGNET_CHALLENGE_DEFAULT.to_vec());
+        assert_eq!(
+            Chain::signet_with_challenge(default.clone()),
+            Chain::Signet
+        );
+        assert_eq!(Chain::Signet.signet_challenge(), Some(default));
+        assert_eq!(Chain::Mainnet.signet_challenge(), None);
+        assert_eq!(
+            Chain::SignetCustom(*Chain::Signet.as_genesis_hash())
+                .signet_challenge(),
+            None
+        );
+
+        let op_true = Script::from_hex("51").unwrap();
+        let chain = Chain::signet_with_challenge(op_true.clone());
+        assert_eq!(chain, Chain::SignetChallenge(op_true.clone()));
+        assert_ne!(chain, Chain::Signet);
+        assert_eq!(chain.as_genesis_hash(), Chain::Signet.as_genesis_hash());
+        assert_eq!(chain.signet_challenge(), Some(op_true));
+        assert_eq!(
+            chain.chain_params().p2p_magic,
+            P2pNetworkId::Other(0xbd6fd254)
+        );
+
+        assert_eq!(chain.to_string(), "signetchallenge:51");
+        assert_eq!(Chain::from_str("signetchallenge:51").unwrap(), chain);
+        assert_eq!(
+            Chain::from_str(&format!(
+                "signetchallenge:{}",
+                Script::from(SIGNET_CHALLENGE_DEFAULT.to_vec()).to_hex()
+            ))
+            .unwrap(),
+            Chain::Signet
+        );
+
+        let data = strict_serialize(&chain).unwrap();
+        let decoded: Chain = strict_deserialize(data).unwrap();
+        assert_eq!(decoded, chain);
+        assert!(matches!(decoded, Chain::SignetChallenge(_)));
+    }
+}
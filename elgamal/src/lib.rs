@@ -67,6 +67,7 @@ impl From<secp256k1::Error> for Error {
 
 /// Encrypts provided byte string using specified encryption and blinding
 /// keys according to LNPBP-31 ElGamal schema
+#[allow(clippy::manual_is_multiple_of)]
 pub fn encrypt<C: Signing + Verification>(
     context: &Secp256k1<C>,
     message: &[u8],
@@ -142,6 +143,7 @@ pub fn encrypt<C: Signing + Verification>(
 
 /// Decrypts provided byte string using specified decryption and unblinding
 /// keys according to LNPBP-31 ElGamal schema
+#[allow(clippy::manual_is_multiple_of)]
 pub fn decrypt<C: Verification>(
     context: &Secp256k1<C>,
     mut encrypted: &[u8],
@@ -194,6 +196,11 @@ pub fn decrypt<C: Verification>(
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::needless_borrow,
+    clippy::needless_borrows_for_generic_args,
+    clippy::clone_on_copy
+)]
 mod test {
     use secp256k1::rand::{thread_rng, Rng, RngCore};
     use secp256k1::SECP256K1;
@@ -227,6 +227,22 @@ impl IdentitySigner {
         }
     }
 
+    // Matches the key returned by `IdentityCert::encryption_key`
+    pub fn decryption_key(&self) -> Option<secp256k1::SecretKey> {
+        match self.cert.algo {
+            EcAlgo::Bip340 => {
+                let sk = secp256k1::SecretKey::from_slice(&self.prvkey)
+                    .expect("invalid private key");
+                let (_, parity) = sk.x_only_public_key(SECP256K1);
+                Some(match parity {
+                    secp256k1::Parity::Even => sk,
+                    secp256k1::Parity::Odd => sk.negate(),
+                })
+            }
+            EcAlgo::Ed25519 => None,
+        }
+    }
+
     pub fn sign_stream(&self, mut input: impl Read) -> io::Result<SigCert> {
         match self.cert.algo {
             EcAlgo::Bip340 => {
@@ -266,7 +282,7 @@ pub struct IdentityCert {
 impl IdentityCert {
     pub fn nym(&self) -> String {
         let mut mnemonic = Vec::with_capacity(64);
-        let mut crc32data = Vec::with_capacity(self.algo.cert_len() as usize);
+        let mut crc32data = Vec::with_capacity(self.algo.cert_len());
         crc32data.push(self.algo.encode());
         crc32data.extend(&*self.pubkey);
         let crc32 = crc32fast::hash(&crc32data);
@@ -283,6 +299,21 @@ impl IdentityCert {
         let _ = s.split_off(6);
         s
     }
+
+    // BIP340 keys are lifted into the full public keys with even Y coordinate
+    pub fn encryption_key(&self) -> Option<secp256k1::PublicKey> {
+        match self.algo {
+            EcAlgo::Bip340 => {
+                let xonly = secp256k1::XOnlyPublicKey::from_slice(&self.pubkey)
+                    .expect("invalid public key");
+                Some(secp256k1::PublicKey::from_x_only_public_key(
+                    xonly,
+                    secp256k1::Parity::Even,
+                ))
+            }
+            EcAlgo::Ed25519 => None,
+        }
+    }
 }
 
 impl StrictEncode for IdentityCert {
@@ -293,7 +324,7 @@ impl StrictEncode for IdentityCert {
         self.algo.strict_encode(&mut e)?;
         e.write_all(&self.pubkey)?;
         e.write_all(&self.sig)?;
-        Ok(self.algo.cert_len() as usize)
+        Ok(self.algo.cert_len())
     }
 }
 
@@ -532,6 +563,7 @@ fn secp_to_sten_err(err: secp256k1::Error) -> strict_encoding::Error {
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod test {
     use std::str::FromStr;
 
@@ -547,6 +579,18 @@ mod test {
         SigCert::from_str("sig1qgqm0d5l8sjas4v2vk3tzqc5m2ltpkv208uf2chyh3fqmhwq3rdnu3ve0gkytrl7wl68075zxxukq9ff6gmd38w7hmtdas089jefkf2rsyasksse").unwrap()
     }
 
+    #[test]
+    fn encryption_keys() {
+        for _ in 0..8 {
+            let signer = IdentitySigner::new_bip340();
+            let sk = signer.decryption_key().unwrap();
+            assert_eq!(
+                Some(secp256k1::PublicKey::from_secret_key(SECP256K1, &sk)),
+                signer.cert.encryption_key()
+            );
+        }
+    }
+
     #[test]
     fn cert_create() {
         let pair = secp256k1::KeyPair::from_seckey_slice(
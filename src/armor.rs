@@ -0,0 +1,760 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! ASCII armor for LNP/BP objects, similar to the one used by OpenPGP
+//! (RFC 4880, section 6.2).
+//!
+//! Armored object looks like:
+//!
+//! ```text
+//! -----BEGIN LNPBP CERTIFICATE-----
+//! Version: 1
+//! Encoding: base64
+//! Nym: venice_vega_balloon
+//!
+//! <line-wrapped base64 or bech32 body>
+//! =<base64-encoded CRC24 checksum of the binary data>
+//! -----END LNPBP CERTIFICATE-----
+//! ```
+//!
+//! The body may be encoded either with Base64, or with Bech32m using the HRP
+//! matching the object type (like `crt`, `sig`, `enc`, `signed` or `data`).
+//! In both cases the checksum is computed over the binary (strict-encoded)
+//! object data.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use bech32_crate::{FromBase32, ToBase32, Variant};
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::bech32::Blob;
+
+/// Version of the armor format produced by this library
+pub const ARMOR_VERSION: u8 = 1;
+
+/// Maximal length of the armored body lines
+pub const ARMOR_LINE_WIDTH: usize = 64;
+
+const ARMOR_DASHES: &str = "-----";
+const ARMOR_BEGIN: &str = "BEGIN LNPBP ";
+const ARMOR_END: &str = "END LNPBP ";
+const HEADER_VERSION: &str = "Version";
+const HEADER_ENCODING: &str = "Encoding";
+
+/// Errors parsing ASCII-armored data
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum Error {
+    /// armored data must start with `-----BEGIN LNPBP <TYPE>-----` line
+    NoBeginMarker,
+
+    /// armored data must end with `-----END LNPBP {0}-----` line
+    NoEndMarker(ArmorType),
+
+    /// unknown type of the armored data `{0}`
+    UnknownType(String),
+
+    /// armored data contain {0} while {1} was expected
+    TypeMismatch(ArmorType, ArmorType),
+
+    /// armored data use unsupported version of the armor format `{0}`
+    UnsupportedVersion(String),
+
+    /// armored data use unknown body encoding `{0}`
+    UnknownEncoding(String),
+
+    /// invalid armor header `{0}`; headers must have `Key: value` form
+    InvalidHeader(String),
+
+    /// invalid armor header key `{0}`; keys must be non-empty and must not
+    /// contain colons, whitespaces or control characters
+    InvalidHeaderKey(String),
+
+    /// armor header `{0}` is defined by the armor format and can't be added
+    ReservedHeader(String),
+
+    /// invalid value `{0}` of an armor header; values must not contain line
+    /// breaks or control characters, and must not start or end with a
+    /// whitespace
+    InvalidHeaderValue(String),
+
+    /// armored data do not contain checksum
+    NoChecksum,
+
+    /// checksum of the armored data does not match the data
+    ChecksumMismatch,
+
+    /// armored body is not a valid base64 string: {0}
+    #[from]
+    Base64(base64::DecodeError),
+
+    /// armored body is not a valid bech32m string: {0}
+    #[from]
+    Bech32(bech32_crate::Error),
+
+    /// armored body must use bech32m encoding instead of legacy bech32
+    Bech32Variant,
+
+    /// armored body uses HRP `{0}` which does not match the armor type
+    /// {1}
+    HrpMismatch(String, ArmorType),
+
+    /// armored data are not a valid strict encoding of the object: {0}
+    #[from]
+    StrictEncoding(strict_encoding::Error),
+
+    /// armored data are not valid encrypted data: {0}
+    #[cfg(feature = "elgamal")]
+    InvalidEncryptedData(crate::bech32::Error),
+}
+
+/// Type of the armored object
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+pub enum ArmorType {
+    /// Identity certificate
+    #[display("CERTIFICATE")]
+    Certificate,
+
+    /// Detached signature
+    #[display("SIGNATURE")]
+    Signature,
+
+    /// Data encrypted according to LNPBP-31
+    #[display("ENCRYPTED MESSAGE")]
+    Encrypted,
+
+    /// Data carrying signature of the signer identity
+    #[display("SIGNED MESSAGE")]
+    Signed,
+
+    /// Arbitrary binary data
+    #[display("DATA")]
+    Data,
+}
+
+impl ArmorType {
+    /// Enumerates all known armor types
+    pub fn all() -> &'static [ArmorType] {
+        &[
+            ArmorType::Certificate,
+            ArmorType::Signature,
+            ArmorType::Encrypted,
+            ArmorType::Signed,
+            ArmorType::Data,
+        ]
+    }
+
+    /// Returns Bech32 HRP used by the objects of this type
+    pub fn hrp(self) -> &'static str {
+        match self {
+            ArmorType::Certificate => "crt",
+            ArmorType::Signature => "sig",
+            ArmorType::Encrypted => "enc",
+            ArmorType::Signed => "signed",
+            ArmorType::Data => crate::bech32::HRP_DATA,
+        }
+    }
+
+    /// Detects armor type from the Bech32 HRP
+    pub fn from_hrp(hrp: &str) -> Option<ArmorType> {
+        ArmorType::all().iter().find(|ty| ty.hrp() == hrp).copied()
+    }
+}
+
+impl FromStr for ArmorType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ArmorType::all()
+            .iter()
+            .find(|ty| ty.to_string() == s)
+            .copied()
+            .ok_or_else(|| Error::UnknownType(s.to_owned()))
+    }
+}
+
+/// Encoding used for the armored body
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+pub enum ArmorEncoding {
+    /// Base64 encoding of the binary object data
+    #[display("base64")]
+    Base64,
+
+    /// Bech32m encoding of the binary object data, using HRP specific to the
+    /// armor type
+    #[display("bech32")]
+    Bech32,
+}
+
+impl FromStr for ArmorEncoding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "base64" => Ok(ArmorEncoding::Base64),
+            "bech32" | "bech32m" => Ok(ArmorEncoding::Bech32),
+            _ => Err(Error::UnknownEncoding(s.to_owned())),
+        }
+    }
+}
+
+/// ASCII-armored object: binary object data with type information and
+/// arbitrary key-value headers
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Armor {
+    armor_type: ArmorType,
+    headers: Vec<(String, String)>,
+    data: Vec<u8>,
+}
+
+impl Armor {
+    /// Constructs armor for binary data of a given type
+    pub fn with(armor_type: ArmorType, data: impl Into<Vec<u8>>) -> Armor {
+        Armor {
+            armor_type,
+            headers: vec![],
+            data: data.into(),
+        }
+    }
+
+    /// Constructs armor from a Bech32m string of the supported object types,
+    /// detecting the armor type from the string HRP
+    pub fn from_bech32_str(s: &str) -> Result<Armor, Error> {
+        let (hrp, data, variant) = bech32_crate::decode(s)?;
+        let armor_type = ArmorType::from_hrp(&hrp)
+            .ok_or_else(|| Error::UnknownType(hrp.to_owned()))?;
+        if variant != Variant::Bech32m {
+            return Err(Error::Bech32Variant);
+        }
+        Ok(Armor::with(armor_type, Vec::<u8>::from_base32(&data)?))
+    }
+
+    /// Adds header to the armor. Fails if the key or value can't be
+    /// represented in the armored string, or if the key is one of the
+    /// format-defined `Version` and `Encoding` headers.
+    pub fn add_header(
+        &mut self,
+        key: impl ToString,
+        value: impl ToString,
+    ) -> Result<&mut Self, Error> {
+        let (key, value) = (key.to_string(), value.to_string());
+        check_header(&key, &value)?;
+        self.headers.push((key, value));
+        Ok(self)
+    }
+
+    /// Returns type of the armored object
+    pub fn armor_type(&self) -> ArmorType { self.armor_type }
+
+    /// Returns value of the first header with a given key, if any
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns all armor headers, except format-defined `Version` and
+    /// `Encoding`
+    pub fn headers(&self) -> &[(String, String)] { &self.headers }
+
+    /// Returns armored binary data
+    pub fn data(&self) -> &[u8] { &self.data }
+
+    /// Releases armored binary data
+    pub fn into_data(self) -> Vec<u8> { self.data }
+
+    /// Returns Bech32m string representing the armored data
+    pub fn to_bech32_string(&self) -> String {
+        bech32_crate::encode(
+            self.armor_type.hrp(),
+            self.data.to_base32(),
+            Variant::Bech32m,
+        )
+        .expect("HRP is hardcoded and can't fail")
+    }
+
+    /// Produces ASCII-armored string with the body using a given encoding
+    pub fn to_armored_string(&self, encoding: ArmorEncoding) -> String {
+        let mut s = format!(
+            "{}{}{}{}\n",
+            ARMOR_DASHES, ARMOR_BEGIN, self.armor_type, ARMOR_DASHES
+        );
+        s.push_str(&format!("{}: {}\n", HEADER_VERSION, ARMOR_VERSION));
+        s.push_str(&format!("{}: {}\n", HEADER_ENCODING, encoding));
+        for (key, value) in &self.headers {
+            s.push_str(&format!("{}: {}\n", key, value));
+        }
+        s.push('\n');
+
+        let body = match encoding {
+            ArmorEncoding::Base64 => base64::encode(&self.data),
+            ArmorEncoding::Bech32 => self.to_bech32_string(),
+        };
+        let mut body = body.as_str();
+        while !body.is_empty() {
+            let (line, rest) = body.split_at(ARMOR_LINE_WIDTH.min(body.len()));
+            s.push_str(line);
+            s.push('\n');
+            body = rest;
+        }
+
+        let crc = crc24(&self.data).to_be_bytes();
+        s.push_str(&format!("={}\n", base64::encode(&crc[1..])));
+        s.push_str(&format!(
+            "{}{}{}{}\n",
+            ARMOR_DASHES, ARMOR_END, self.armor_type, ARMOR_DASHES
+        ));
+        s
+    }
+}
+
+impl Display for Armor {
+    /// Formats armor with Base64-encoded body; alternate formatting (`{:#}`)
+    /// uses Bech32m body encoding
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let encoding = if f.alternate() {
+            ArmorEncoding::Bech32
+        } else {
+            ArmorEncoding::Base64
+        };
+        f.write_str(&self.to_armored_string(encoding))
+    }
+}
+
+impl FromStr for Armor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim).skip_while(|l| l.is_empty());
+
+        let armor_type = lines
+            .next()
+            .and_then(|l| l.strip_prefix(ARMOR_DASHES))
+            .and_then(|l| l.strip_suffix(ARMOR_DASHES))
+            .and_then(|l| l.strip_prefix(ARMOR_BEGIN))
+            .ok_or(Error::NoBeginMarker)?
+            .parse::<ArmorType>()?;
+
+        let mut encoding = ArmorEncoding::Base64;
+        let mut headers = vec![];
+        for line in lines.by_ref().take_while(|l| !l.is_empty()) {
+            // Lines are trimmed, so headers with empty value have no space
+            // after the colon
+            let (key, value) = line
+                .split_once(':')
+                .map(|(key, value)| (key, value.trim_start()))
+                .ok_or_else(|| Error::InvalidHeader(line.to_owned()))?;
+            match key {
+                HEADER_VERSION if value == ARMOR_VERSION.to_string() => {}
+                HEADER_VERSION => {
+                    return Err(Error::UnsupportedVersion(value.to_owned()))
+                }
+                HEADER_ENCODING => encoding = value.parse()?,
+                _ => {
+                    check_header(key, value)
+                        .map_err(|_| Error::InvalidHeader(line.to_owned()))?;
+                    headers.push((key.to_owned(), value.to_owned()))
+                }
+            }
+        }
+
+        let mut body = String::new();
+        let mut checksum = None;
+        let end_marker = format!(
+            "{}{}{}{}",
+            ARMOR_DASHES, ARMOR_END, armor_type, ARMOR_DASHES
+        );
+        let mut ended = false;
+        for line in lines {
+            if line == end_marker {
+                ended = true;
+                break;
+            } else if let Some(crc) = line.strip_prefix('=') {
+                checksum = Some(base64::decode(crc)?);
+            } else {
+                body.push_str(line);
+            }
+        }
+        if !ended {
+            return Err(Error::NoEndMarker(armor_type));
+        }
+
+        let data = match encoding {
+            ArmorEncoding::Base64 => base64::decode(&body)?,
+            ArmorEncoding::Bech32 => {
+                let armor = Armor::from_bech32_str(&body)?;
+                if armor.armor_type != armor_type {
+                    let (hrp, ..) = bech32_crate::decode(&body)?;
+                    return Err(Error::HrpMismatch(hrp, armor_type));
+                }
+                armor.data
+            }
+        };
+
+        let checksum = checksum.ok_or(Error::NoChecksum)?;
+        if checksum[..] != crc24(&data).to_be_bytes()[1..] {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        Ok(Armor {
+            armor_type,
+            headers,
+            data,
+        })
+    }
+}
+
+/// Checks that the header can be represented in the armored string and does
+/// not clash with the format-defined headers
+fn check_header(key: &str, value: &str) -> Result<(), Error> {
+    if key.is_empty()
+        || key
+            .chars()
+            .any(|c| c == ':' || c.is_whitespace() || c.is_control())
+    {
+        return Err(Error::InvalidHeaderKey(key.to_owned()));
+    }
+    if key.eq_ignore_ascii_case(HEADER_VERSION)
+        || key.eq_ignore_ascii_case(HEADER_ENCODING)
+    {
+        return Err(Error::ReservedHeader(key.to_owned()));
+    }
+    if value.chars().any(char::is_control) || value.trim() != value {
+        return Err(Error::InvalidHeaderValue(value.to_owned()));
+    }
+    Ok(())
+}
+
+/// Objects which can be represented in ASCII-armored form
+pub trait Armored: StrictEncode + StrictDecode {
+    /// Type of the armored object
+    const ARMOR_TYPE: ArmorType;
+
+    /// Returns object-specific armor headers
+    fn armor_headers(&self) -> Vec<(String, String)> { vec![] }
+
+    /// Constructs armor for the object
+    fn to_armor(&self) -> Armor {
+        let mut armor = Armor::with(
+            Self::ARMOR_TYPE,
+            self.strict_serialize()
+                .expect("in-memory strict encoding failure"),
+        );
+        armor.headers = self.armor_headers();
+        armor
+    }
+
+    /// Returns ASCII-armored string representing the object
+    fn to_armored_string(&self) -> String { self.to_armor().to_string() }
+
+    /// Reconstructs object from the armor
+    fn from_armor(armor: &Armor) -> Result<Self, Error> {
+        if armor.armor_type != Self::ARMOR_TYPE {
+            return Err(Error::TypeMismatch(
+                armor.armor_type,
+                Self::ARMOR_TYPE,
+            ));
+        }
+        Ok(Self::strict_deserialize(&armor.data)?)
+    }
+
+    /// Reconstructs object from the ASCII-armored string
+    fn from_armored_str(s: &str) -> Result<Self, Error> {
+        Self::from_armor(&Armor::from_str(s)?)
+    }
+}
+
+impl Armored for Blob {
+    const ARMOR_TYPE: ArmorType = ArmorType::Data;
+
+    // We armor raw blob data without length prefix, such that the Bech32 body
+    // matches `data1...` blob representation
+    fn to_armor(&self) -> Armor { Armor::with(Self::ARMOR_TYPE, self.to_vec()) }
+
+    fn from_armor(armor: &Armor) -> Result<Self, Error> {
+        if armor.armor_type != Self::ARMOR_TYPE {
+            return Err(Error::TypeMismatch(
+                armor.armor_type,
+                Self::ARMOR_TYPE,
+            ));
+        }
+        Ok(Blob::from(armor.data.clone()))
+    }
+}
+
+#[cfg(feature = "identity")]
+mod identity {
+    use super::*;
+    use crate::id::{IdentityCert, SigCert};
+    use crate::signed::SignedPayload;
+
+    impl Armored for IdentityCert {
+        const ARMOR_TYPE: ArmorType = ArmorType::Certificate;
+
+        fn armor_headers(&self) -> Vec<(String, String)> {
+            vec![(s!("Nym"), self.nym())]
+        }
+    }
+
+    impl Armored for SigCert {
+        const ARMOR_TYPE: ArmorType = ArmorType::Signature;
+    }
+
+    impl Armored for SignedPayload {
+        const ARMOR_TYPE: ArmorType = ArmorType::Signed;
+
+        fn armor_headers(&self) -> Vec<(String, String)> {
+            vec![(s!("Signer"), self.signer().to_string())]
+        }
+    }
+}
+
+#[cfg(feature = "elgamal")]
+mod encrypted {
+    use super::*;
+    use crate::bech32::EncryptedData;
+
+    impl Armored for EncryptedData {
+        const ARMOR_TYPE: ArmorType = ArmorType::Encrypted;
+
+        // We armor the same payload which is used by `enc1...` strings, such
+        // that the Bech32 body matches them
+        fn to_armor(&self) -> Armor {
+            Armor::with(Self::ARMOR_TYPE, self.to_payload())
+        }
+
+        fn from_armor(armor: &Armor) -> Result<Self, Error> {
+            if armor.armor_type != Self::ARMOR_TYPE {
+                return Err(Error::TypeMismatch(
+                    armor.armor_type,
+                    Self::ARMOR_TYPE,
+                ));
+            }
+            EncryptedData::from_payload(&armor.data)
+                .map_err(Error::InvalidEncryptedData)
+        }
+    }
+}
+
+/// Computes CRC24 checksum as defined in RFC 4880, section 6.1
+fn crc24(data: &[u8]) -> u32 {
+    const CRC24_INIT: u32 = 0xB704CE;
+    const CRC24_POLY: u32 = 0x1864CFB;
+
+    let mut crc = CRC24_INIT;
+    for byte in data {
+        crc ^= (*byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0xFFFFFF
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn crc24_vectors() {
+        assert_eq!(crc24(b""), 0xB704CE);
+        assert_eq!(crc24(b"123456789"), 0x21CF02);
+    }
+
+    #[test]
+    fn armor_roundtrip() {
+        let blob = Blob::from(vec![0xA5u8; 100]);
+        let mut armor = blob.to_armor();
+        armor.add_header("Comment", "some comment").unwrap();
+
+        let s = armor.to_string();
+        assert!(s.starts_with("-----BEGIN LNPBP DATA-----\nVersion: 1\n"));
+        assert!(s.ends_with("-----END LNPBP DATA-----\n"));
+        assert!(s.lines().all(|l| l.len() <= ARMOR_LINE_WIDTH));
+        let decoded = Armor::from_str(&s).unwrap();
+        assert_eq!(decoded, armor);
+        assert_eq!(decoded.header("Comment"), Some("some comment"));
+        assert_eq!(Blob::from_armor(&decoded).unwrap(), blob);
+
+        let s = format!("{:#}", armor);
+        assert!(s.contains("Encoding: bech32\n"));
+        assert!(s.contains(&format!("\n\n{}", &blob.to_string()[..64])));
+        assert_eq!(Armor::from_str(&s).unwrap(), armor);
+        assert_eq!(
+            Armor::from_bech32_str(&armor.to_bech32_string())
+                .unwrap()
+                .data(),
+            armor.data()
+        );
+    }
+
+    #[test]
+    fn armor_headers() {
+        let mut armor = Blob::from(b"some data".to_vec()).to_armor();
+        armor
+            .add_header("Empty", "")
+            .unwrap()
+            .add_header("Colon", "a: b")
+            .unwrap()
+            .add_header("Unicode", "value with spaces")
+            .unwrap()
+            .add_header("Comment", "repeated")
+            .unwrap()
+            .add_header("Comment", "")
+            .unwrap();
+        for s in [armor.to_string(), format!("{:#}", armor)] {
+            let decoded = Armor::from_str(&s).unwrap();
+            assert_eq!(decoded, armor);
+            assert_eq!(decoded.header("Empty"), Some(""));
+            assert_eq!(decoded.header("Colon"), Some("a: b"));
+        }
+
+        for key in ["", "Two words", "Key:", "Tab\t", "New\nline"] {
+            assert_eq!(
+                armor.add_header(key, "value").unwrap_err(),
+                Error::InvalidHeaderKey(key.to_owned())
+            );
+        }
+        for key in ["Version", "Encoding", "encoding"] {
+            assert_eq!(
+                armor.add_header(key, "base64").unwrap_err(),
+                Error::ReservedHeader(key.to_owned())
+            );
+        }
+        for value in ["a\nEncoding: bech32", "a\r", " a", "a ", "\t"] {
+            assert_eq!(
+                armor.add_header("Comment", value).unwrap_err(),
+                Error::InvalidHeaderValue(value.to_owned())
+            );
+        }
+        assert_eq!(armor.headers().len(), 5);
+
+        let s = armor.to_string();
+        assert_eq!(
+            Armor::from_str(&s.replace("Colon: a: b", "Bad key: a"))
+                .unwrap_err(),
+            Error::InvalidHeader(s!("Bad key: a"))
+        );
+    }
+
+    #[test]
+    fn armor_errors() {
+        let blob = Blob::from(b"some data".to_vec());
+        let s = blob.to_armored_string();
+
+        assert_eq!(
+            Armor::from_str(&s.replace("BEGIN", "START")).unwrap_err(),
+            Error::NoBeginMarker
+        );
+        assert_eq!(
+            Armor::from_str(&s.replace("END LNPBP", "STOP")).unwrap_err(),
+            Error::NoEndMarker(ArmorType::Data)
+        );
+        assert_eq!(
+            Armor::from_str(&s.replace("Version: 1", "Version: 2"))
+                .unwrap_err(),
+            Error::UnsupportedVersion(s!("2"))
+        );
+        assert_eq!(
+            Armor::from_str(&s.replace("c29tZSBkYXRh", "c29tZSBkYXRi"))
+                .unwrap_err(),
+            Error::ChecksumMismatch
+        );
+        assert_eq!(
+            Armor::from_str(
+                &s.lines()
+                    .filter(|l| !l.starts_with('='))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+            .unwrap_err(),
+            Error::NoChecksum
+        );
+        assert_eq!(
+            Armor::from_str(&s.replace("DATA", "UNKNOWN")).unwrap_err(),
+            Error::UnknownType(s!("UNKNOWN"))
+        );
+
+        let armor = Armor::with(ArmorType::Signature, vec![]);
+        assert_eq!(
+            Blob::from_armor(&armor).unwrap_err(),
+            Error::TypeMismatch(ArmorType::Signature, ArmorType::Data)
+        );
+    }
+
+    #[cfg(feature = "identity")]
+    #[test]
+    fn armor_identity() {
+        use crate::id::{IdentityCert, IdentitySigner};
+        use crate::signed::SignedPayload;
+
+        let me = IdentitySigner::new_bip340();
+        let s = me.cert.to_armored_string();
+        let armor = Armor::from_str(&s).unwrap();
+        assert_eq!(armor.armor_type(), ArmorType::Certificate);
+        assert_eq!(armor.header("Nym"), Some(me.cert.nym().as_str()));
+        assert_eq!(IdentityCert::from_armor(&armor).unwrap(), me.cert);
+        let bech32 = format!("{}", me.cert);
+        assert!(bech32.starts_with(&armor.to_bech32_string()));
+
+        let signed = SignedPayload::sign(&me, b"some data".to_vec());
+        let s = format!("{:#}", signed.to_armor());
+        assert_eq!(SignedPayload::from_armored_str(&s).unwrap(), signed);
+        assert_eq!(
+            Armor::from_bech32_str(&signed.to_string()).unwrap(),
+            Armor::with(ArmorType::Signed, signed.strict_serialize().unwrap())
+        );
+    }
+
+    #[cfg(feature = "elgamal")]
+    #[test]
+    fn armor_encrypted() {
+        use secp256k1::{rand, PublicKey, SecretKey, SECP256K1};
+
+        use crate::bech32::{EncryptedData, Error as Bech32Error};
+
+        let decryption_key = SecretKey::new(&mut rand::thread_rng());
+        let recipient = PublicKey::from_secret_key(SECP256K1, &decryption_key);
+        let blob = Blob::from(vec![0x5Au8; 200]);
+        let encrypted = EncryptedData::encrypt(&blob, recipient).unwrap();
+
+        for s in [
+            encrypted.to_armored_string(),
+            format!("{:#}", encrypted.to_armor()),
+        ] {
+            assert!(s.starts_with("-----BEGIN LNPBP ENCRYPTED MESSAGE-----"));
+            assert!(s.lines().all(|l| l.len() <= ARMOR_LINE_WIDTH));
+            let decoded = EncryptedData::from_armored_str(&s).unwrap();
+            assert_eq!(decoded, encrypted);
+            assert_eq!(
+                decoded.decrypt::<Blob>(&decryption_key),
+                Ok(blob.clone())
+            );
+        }
+        assert_eq!(
+            Armor::from_bech32_str(&encrypted.to_string()).unwrap(),
+            encrypted.to_armor()
+        );
+
+        let armor = Armor::with(ArmorType::Encrypted, vec![2u8; 40]);
+        assert_eq!(
+            EncryptedData::from_armor(&armor).unwrap_err(),
+            Error::InvalidEncryptedData(Bech32Error::UnknownEncryption(2))
+        );
+    }
+}
@@ -25,10 +25,13 @@ use std::string::FromUtf8Error;
 use std::{fmt, fs};
 
 use amplify::hex::{self, FromHex, ToHex};
+use amplify::Wrapper;
 use base58::{FromBase58, FromBase58Error, ToBase58};
 use clap::Parser;
 use colorize::AnsiColor;
-use lnpbp::bech32::Blob;
+use lnpbp::armor::{self, Armor, ArmorType, Armored};
+use lnpbp::bech32::{Blob, EncryptedData};
+use lnpbp::signed::{self, SignedPayload};
 use lnpbp::{bech32, id};
 use lnpbp_identity::{
     EcAlgo, IdentityCert, IdentitySigner, SigCert, VerifyError,
@@ -67,7 +70,7 @@ pub enum Command {
         into: Format,
 
         /// Original data string
-        #[clap(short, long, conflicts_with = "file")]
+        #[clap(short, long, conflicts_with = "input-file")]
         data: Option<String>,
 
         /// File with the source data. If no `--data` option is given reads
@@ -103,6 +106,16 @@ pub enum IdentityCommand {
 
     /// Sign a message, a file or data read from STDIN
     Sign {
+        /// Embed the signed data into the output, producing a single
+        /// `signed1...` string carrying both the data and the signature
+        #[clap(short, long)]
+        embed: bool,
+
+        /// Embed only a fingerprint of the identity certificate instead of the
+        /// full certificate; requires `--embed`
+        #[clap(long, requires = "embed")]
+        fingerprint_only: bool,
+
         /// File containing identity information
         #[clap()]
         identity_file: PathBuf,
@@ -119,13 +132,23 @@ pub enum IdentityCommand {
     /// Verify an identity certificate and optionally a signature against a
     /// file, message or data read from STDIN
     Verify {
-        /// An identity certificate to use
-        #[clap()]
-        cert: IdentityCert,
+        /// Signed data (`signed1...` string) carrying both the data and the
+        /// signature
+        #[clap(
+            short,
+            long,
+            conflicts_with_all = &["sig", "message", "message-file"]
+        )]
+        signed: Option<SignedPayload>,
+
+        /// An identity certificate to use. Optional for signed data
+        /// embedding the certificate
+        #[clap(required_unless_present = "signed")]
+        cert: Option<IdentityCert>,
 
         /// A signature to verify
-        #[clap()]
-        sig: SigCert,
+        #[clap(required_unless_present = "signed")]
+        sig: Option<SigCert>,
 
         /// Message to verify the signature
         #[clap(short, long = "msg")]
@@ -136,22 +159,20 @@ pub enum IdentityCommand {
         message_file: Option<PathBuf>,
     },
 
-    /// Encrypt a message
+    /// Encrypt a message for a receiver, producing `enc1...` string
+    ///
+    /// The message is encrypted as is; its size is limited to 4 GiB.
     Encrypt {
         /// Use ASCII armoring
         #[clap(short, long = "ascii")]
         armor: bool,
 
-        /// File containing local information
-        #[clap()]
-        identity_file: PathBuf,
-
         /// An identity of the receiver
         #[clap()]
         cert: IdentityCert,
 
         /// Message to encrypt
-        #[clap(short, long = "msg", conflicts_with = "file")]
+        #[clap(short, long = "msg", conflicts_with = "src-file")]
         message: Option<String>,
 
         /// File to encrypt
@@ -169,16 +190,12 @@ pub enum IdentityCommand {
         #[clap(short, long = "ascii")]
         armor: bool,
 
-        /// File containing local information
+        /// File containing identity of the receiver
         #[clap()]
         identity_file: PathBuf,
 
-        /// An identity of the receiver
-        #[clap()]
-        cert: IdentityCert,
-
         /// Message to decrypt
-        #[clap(short, long = "msg", conflicts_with = "file")]
+        #[clap(short, long = "msg", conflicts_with = "src-file")]
         message: Option<String>,
 
         /// File to decrypt
@@ -230,6 +247,10 @@ pub enum Format {
     /// Produce binary (raw) output
     #[display("raw")]
     Raw,
+
+    /// Format as ASCII armor
+    #[display("armor")]
+    Armor,
 }
 
 impl FromStr for Format {
@@ -246,6 +267,7 @@ impl FromStr for Format {
             "hex" | "base32" => Format::Hexadecimal,
             "raw" | "bin" | "binary" => Format::Raw,
             "rust" => Format::Rust,
+            "armor" | "ascii" => Format::Armor,
             other => return Err(format!("Unknown format: {}", other)),
         })
     }
@@ -288,11 +310,27 @@ pub enum Error {
     #[from]
     StrictEncoding(strict_encoding::Error),
 
+    #[display("incorrect ASCII armor. Details: {0}")]
+    #[from]
+    Armor(armor::Error),
+
     #[display("can't read data from {0} format")]
     UnsupportedFormat(Format),
 
     #[from]
     Signature(VerifyError),
+
+    #[from]
+    SignedPayload(signed::Error),
+
+    #[display(
+        "identity certificate and signature are required unless signed data \
+         are provided"
+    )]
+    VerifyArgsMissing,
+
+    #[display("only BIP340 identities support encryption")]
+    EncryptionUnsupported,
 }
 
 impl Debug for Error {
@@ -319,6 +357,7 @@ where
         Format::Yaml => serde_yaml::from_str(s)?,
         Format::Json => serde_json::from_str(s)?,
         Format::Hexadecimal => T::from(Vec::<u8>::from_hex(s)?),
+        Format::Armor => T::from(Blob::from_armored_str(s)?.into_inner()),
         _ => return Err(Error::UnsupportedFormat(format)),
     })
 }
@@ -341,6 +380,9 @@ where
         Format::Hexadecimal => write!(f, "{}", data.as_ref().to_hex()),
         Format::Rust => write!(f, "{:#04X?}", data.as_ref()),
         Format::Raw => f.write(data.as_ref()).map(|_| ()),
+        Format::Armor => {
+            write!(f, "{}", Armor::with(ArmorType::Data, data.as_ref()))
+        }
     }
     .map_err(Error::from)
 }
@@ -409,29 +451,102 @@ fn main() -> Result<(), Error> {
             println!("{:?}", id.cert);
         }
         Command::Identity(IdentityCommand::Sign {
+            embed,
+            fingerprint_only,
             identity_file,
             message,
             message_file,
         }) => {
             let fd = fs::File::open(identity_file)?;
             let id = IdentitySigner::strict_decode(fd)?;
-            let input = file_str_or_stdin(message_file, message)?;
-            let sig = id.sign_stream(input)?;
-            println!("{}", sig);
+            let mut input = file_str_or_stdin(message_file, message)?;
+            if embed {
+                let mut data = vec![];
+                input.read_to_end(&mut data)?;
+                let signed = if fingerprint_only {
+                    SignedPayload::sign_with_fingerprint(&id, data)
+                } else {
+                    SignedPayload::sign(&id, data)
+                };
+                println!("{}", signed);
+            } else {
+                let sig = id.sign_stream(input)?;
+                println!("{}", sig);
+            }
         }
         Command::Identity(IdentityCommand::Verify {
+            signed: Some(signed),
+            cert,
+            ..
+        }) => {
+            match cert {
+                Some(cert) => signed.verify_with(&cert)?,
+                None => {
+                    signed.verify()?;
+                }
+            }
+            println!("{}", "Signature is valid".green());
+            println!("Signed by {}", signed.signer());
+        }
+        Command::Identity(IdentityCommand::Verify {
+            signed: None,
             cert,
             sig,
             message,
             message_file,
         }) => {
+            let (cert, sig) = match (cert, sig) {
+                (Some(cert), Some(sig)) => (cert, sig),
+                _ => return Err(Error::VerifyArgsMissing),
+            };
             let mut input = file_str_or_stdin(message_file, message)?;
             let mut data = vec![];
             input.read_to_end(&mut data)?;
             sig.verify(&cert, data)?;
             println!("{}", "Signature is valid".green());
         }
-        Command::Identity(_) => todo!("elgamal encryption support"),
+        Command::Identity(IdentityCommand::Encrypt {
+            armor,
+            cert,
+            message,
+            src_file,
+            dst_file,
+        }) => {
+            let recipient =
+                cert.encryption_key().ok_or(Error::EncryptionUnsupported)?;
+            let mut input = file_str_or_stdin(src_file, message)?;
+            let mut data = vec![];
+            input.read_to_end(&mut data)?;
+            let encrypted = EncryptedData::encrypt_bytes(&data, recipient)?;
+            let mut output = file_or_stdout(dst_file)?;
+            if armor {
+                write!(output, "{}", encrypted.to_armored_string())?;
+            } else {
+                writeln!(output, "{}", encrypted)?;
+            }
+        }
+        Command::Identity(IdentityCommand::Decrypt {
+            armor,
+            identity_file,
+            message,
+            src_file,
+            dst_file,
+        }) => {
+            let fd = fs::File::open(identity_file)?;
+            let id = IdentitySigner::strict_decode(fd)?;
+            let decryption_key =
+                id.decryption_key().ok_or(Error::EncryptionUnsupported)?;
+            let mut input = file_str_or_stdin(src_file, message)?;
+            let mut s = String::new();
+            input.read_to_string(&mut s)?;
+            let encrypted = if armor {
+                EncryptedData::from_armored_str(&s)?
+            } else {
+                EncryptedData::from_str(s.trim())?
+            };
+            let data = encrypted.decrypt_bytes(&decryption_key)?;
+            file_or_stdout(dst_file)?.write_all(&data)?;
+        }
         Command::Convert {
             data,
             from,
@@ -24,6 +24,12 @@
 //!   blockchains;
 //! - [`lnpbp_elgamal`]: library implementing LNPBP-31 standard for ElGamal
 //!   encryption using Secp256k1 curve;
+//! - [`lnpbp_identity`]: library implementing LNP/BP identity certificates and
+//!   signatures.
+//!
+//! The umbrella crate also provides [`signed`] payloads, combining LNPBP-14
+//! Bech32 encoding with LNP/BP identities, and PGP-style ASCII [`armor`] for
+//! LNP/BP objects.
 //!
 //! Other libraries, implementing LNP/BP specifications, not included in this
 //! crate:
@@ -38,9 +44,16 @@
 #![recursion_limit = "256"]
 #![deny(dead_code, missing_docs, warnings)]
 
+#[macro_use]
+extern crate amplify;
+
 pub extern crate lnpbp_bech32 as bech32;
 pub extern crate lnpbp_chain as chain;
 #[cfg(feature = "elgamal")]
 pub extern crate lnpbp_elgamal as elgamal;
 #[cfg(feature = "identity")]
 pub extern crate lnpbp_identity as id;
+
+pub mod armor;
+#[cfg(feature = "identity")]
+pub mod signed;
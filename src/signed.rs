@@ -0,0 +1,259 @@
+// LNP/BP lLibraries implementing LNPBP specifications & standards
+// Written in 2022 by
+//     Dr. Maxim Orlovsky <orlovsky@pandoracore.com>
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the MIT License
+// along with this software.
+// If not, see <https://opensource.org/licenses/MIT>.
+
+//! Signed data payloads, combining LNPBP-14 Bech32 encoding with LNP/BP
+//! identities.
+//!
+//! [`SignedPayload`] bundles arbitrary binary data together with the
+//! information about the signer (either the full [`IdentityCert`] or just its
+//! fingerprint) and a [`SigCert`] signature over the data. It is represented
+//! as a single `signed1...` Bech32m string, so the data and the proof of its
+//! origin can be shared together.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::bech32::{
+    self, strategies, Blob, FromBech32Str, Strategy, ToBech32String,
+};
+use crate::id::{IdentityCert, IdentitySigner, SigCert, VerifyError};
+
+/// Bech32 HRP used for representation of signed data payloads
+pub const HRP_SIGNED: &str = "signed";
+
+/// Errors verifying signed payload
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum Error {
+    /// signed payload does not embed signer certificate; a certificate with
+    /// fingerprint {0} must be provided for the verification
+    CertRequired(String),
+
+    /// provided certificate with fingerprint {0} does not match the payload
+    /// signer {1}
+    CertMismatch(String, String),
+
+    /// payload signature is not valid: {0}
+    #[from]
+    InvalidSig(VerifyError),
+}
+
+/// Information about the payload signer
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictEncode, StrictDecode)]
+pub enum Signer {
+    /// Full identity certificate of the signer, allowing standalone payload
+    /// verification
+    Cert(IdentityCert),
+
+    /// Fingerprint of the signer identity certificate; the certificate itself
+    /// must be provided separately for the payload verification
+    Fingerprint(String),
+}
+
+impl Signer {
+    /// Returns fingerprint of the signer identity certificate
+    pub fn fingerprint(&self) -> String {
+        match self {
+            Signer::Cert(cert) => cert.fingerprint(),
+            Signer::Fingerprint(fingerprint) => fingerprint.clone(),
+        }
+    }
+
+    /// Returns signer identity certificate, if it is known
+    pub fn cert(&self) -> Option<&IdentityCert> {
+        match self {
+            Signer::Cert(cert) => Some(cert),
+            Signer::Fingerprint(_) => None,
+        }
+    }
+}
+
+impl Display for Signer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Signer::Cert(cert) => write!(f, "{:#}", cert),
+            Signer::Fingerprint(fingerprint) => f.write_str(fingerprint),
+        }
+    }
+}
+
+/// Binary data signed with LNP/BP identity, represented as a single
+/// `signed1...` Bech32m string
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictEncode, StrictDecode)]
+pub struct SignedPayload {
+    payload: Blob,
+    signer: Signer,
+    sig: SigCert,
+}
+
+impl SignedPayload {
+    /// Signs the payload, embedding full signer certificate, such that the
+    /// payload can be verified without any additional information
+    pub fn sign(signer: &IdentitySigner, payload: impl Into<Vec<u8>>) -> Self {
+        SignedPayload::with(signer, payload, Signer::Cert(signer.cert.clone()))
+    }
+
+    /// Signs the payload, embedding just a fingerprint of the signer
+    /// certificate. This produces shorter strings, but requires the
+    /// certificate to be provided to [`SignedPayload::verify_with`]
+    pub fn sign_with_fingerprint(
+        signer: &IdentitySigner,
+        payload: impl Into<Vec<u8>>,
+    ) -> Self {
+        let fingerprint = signer.cert.fingerprint();
+        SignedPayload::with(signer, payload, Signer::Fingerprint(fingerprint))
+    }
+
+    fn with(
+        identity: &IdentitySigner,
+        payload: impl Into<Vec<u8>>,
+        signer: Signer,
+    ) -> Self {
+        let payload = Blob::from(payload.into());
+        let sig = identity.sign(&payload);
+        SignedPayload {
+            payload,
+            signer,
+            sig,
+        }
+    }
+
+    /// Returns signed data
+    pub fn payload(&self) -> &[u8] { self.payload.as_ref() }
+
+    /// Releases signed data
+    pub fn into_payload(self) -> Vec<u8> { self.payload.into() }
+
+    /// Returns information about the payload signer
+    pub fn signer(&self) -> &Signer { &self.signer }
+
+    /// Returns signature over the payload
+    pub fn sig(&self) -> &SigCert { &self.sig }
+
+    /// Verifies the payload signature using certificate embedded into the
+    /// payload. Returns the certificate of the signer on success.
+    pub fn verify(&self) -> Result<&IdentityCert, Error> {
+        let cert = self
+            .signer
+            .cert()
+            .ok_or_else(|| Error::CertRequired(self.signer.fingerprint()))?;
+        self.sig.verify(cert, &self.payload)?;
+        Ok(cert)
+    }
+
+    /// Verifies the payload signature against the provided certificate. Fails
+    /// if the certificate does not match the signer information embedded
+    /// into the payload.
+    pub fn verify_with(&self, cert: &IdentityCert) -> Result<(), Error> {
+        let matches = match &self.signer {
+            Signer::Cert(signer) => signer == cert,
+            Signer::Fingerprint(fingerprint) => {
+                *fingerprint == cert.fingerprint()
+            }
+        };
+        if !matches {
+            return Err(Error::CertMismatch(
+                cert.fingerprint(),
+                self.signer.fingerprint(),
+            ));
+        }
+        self.sig.verify(cert, &self.payload)?;
+        Ok(())
+    }
+}
+
+impl Strategy for SignedPayload {
+    const HRP: &'static str = HRP_SIGNED;
+    type Strategy = strategies::UsingStrictEncoding;
+}
+
+impl Display for SignedPayload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_bech32_string())
+    }
+}
+
+impl FromStr for SignedPayload {
+    type Err = bech32::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SignedPayload::from_bech32_str(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn signed_roundtrip() {
+        let me = IdentitySigner::new_bip340();
+        let signed = SignedPayload::sign(&me, b"some data".to_vec());
+        assert_eq!(signed.verify().unwrap(), &me.cert);
+        signed.verify_with(&me.cert).unwrap();
+
+        let s = signed.to_string();
+        assert!(s.starts_with("signed1"));
+        let decoded = SignedPayload::from_str(&s).unwrap();
+        assert_eq!(decoded, signed);
+        assert_eq!(decoded.payload(), b"some data");
+        decoded.verify().unwrap();
+    }
+
+    #[test]
+    fn signed_fingerprint() {
+        let me = IdentitySigner::new_bip340();
+        let other = IdentitySigner::new_bip340();
+        let signed =
+            SignedPayload::sign_with_fingerprint(&me, b"some data".to_vec());
+        assert_eq!(
+            signed.verify().unwrap_err(),
+            Error::CertRequired(me.cert.fingerprint())
+        );
+        let decoded = SignedPayload::from_str(&signed.to_string()).unwrap();
+        decoded.verify_with(&me.cert).unwrap();
+        assert_eq!(
+            decoded.verify_with(&other.cert).unwrap_err(),
+            Error::CertMismatch(
+                other.cert.fingerprint(),
+                me.cert.fingerprint()
+            )
+        );
+    }
+
+    #[test]
+    fn signed_tampered() {
+        let me = IdentitySigner::new_bip340();
+        let mut signed = SignedPayload::sign(&me, b"some data".to_vec());
+        signed.payload = Blob::from(b"other data".to_vec());
+        assert_eq!(
+            signed.verify().unwrap_err(),
+            Error::InvalidSig(VerifyError::InvalidSig)
+        );
+    }
+
+    #[test]
+    fn signed_wrong_hrp() {
+        let me = IdentitySigner::new_bip340();
+        let signed = SignedPayload::sign(&me, b"some data".to_vec());
+        let sig = signed.sig().to_string();
+        assert_eq!(
+            SignedPayload::from_str(&sig).unwrap_err(),
+            bech32::Error::WrongPrefix
+        );
+    }
+}